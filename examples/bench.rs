@@ -1,3 +1,5 @@
+#![allow(clippy::approx_constant)]
+
 use std::{fmt::Write, fs, time::Instant};
 
 const ITERATIONS: u32 = 1_000_000;
@@ -1,4 +1,4 @@
-use formatx::formatx;
+use formatx::{Arg, ToArg, formatx};
 
 #[derive(Debug)]
 struct Point {
@@ -12,6 +12,13 @@ impl std::fmt::Display for Point {
     }
 }
 
+// Lets `Renderer::named` borrow a point; the macros don't need it.
+impl ToArg for Point {
+    fn to_arg(&self) -> Arg<'_> {
+        Arg::new(self)
+    }
+}
+
 fn main() {
     let origin = Point { x: 0.0, y: 0.0 };
     let target = Point { x: 3.5, y: -2.1 };
@@ -0,0 +1,186 @@
+//! Derive macro for [`formatx`](https://docs.rs/formatx).
+//!
+//! Use it through the `derive` feature of `formatx`, which re-exports
+//! [`FormatArgs`](macro@FormatArgs).
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, parse_macro_input, spanned::Spanned};
+
+/// Derive `formatx::ArgSource` and `formatx::FormatArgs` for a struct with
+/// named fields, so its fields can be used as named arguments.
+///
+/// Fields must implement `Display + Debug`, unless marked `nested` or `skip`.
+/// Fields implementing `formatx::ToArg` are converted with it, so integer
+/// fields also support `{:x}` and the other traits `std::fmt` implements.
+///
+/// # Attributes
+///
+/// On the struct:
+///
+/// - `#[formatx(crate = "path")]` - refer to `formatx` through `path`, if it
+///   was renamed in `Cargo.toml` or is re-exported by another crate.
+///
+/// On fields:
+///
+/// - `#[formatx(rename = "name")]` - use `name` instead of the field name.
+/// - `#[formatx(skip)]` - don't expose the field.
+/// - `#[formatx(nested)]` - expose the field for `{field.inner}` paths only.
+///   The field type must implement `formatx::ArgSource`.
+///
+/// Argument names must be ASCII identifiers, as templates can't refer to any
+/// other name:
+///
+/// ```compile_fail
+/// #[derive(formatx::FormatArgs)]
+/// struct User {
+///     #[formatx(rename = "first name")]
+///     first_name: String,
+/// }
+/// ```
+#[proc_macro_derive(FormatArgs, attributes(formatx))]
+pub fn derive_format_args(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Options parsed from `#[formatx(...)]` on a field.
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<LitStr>,
+    skip: bool,
+    nested: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "`FormatArgs` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(
+            data.fields.span(),
+            "`FormatArgs` can only be derived for structs with named fields",
+        ));
+    };
+
+    let krate = crate_path(&input)?;
+    let mut names = Vec::new();
+    let mut arms = Vec::new();
+
+    for field in &fields.named {
+        let options = field_options(field)?;
+        if options.skip {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("named field");
+        let (name, span) = match &options.rename {
+            Some(rename) => (rename.value(), rename.span()),
+            None => (
+                ident.to_string().trim_start_matches("r#").to_string(),
+                ident.span(),
+            ),
+        };
+        if !is_argument_name(&name) {
+            return Err(syn::Error::new(
+                span,
+                format!("`{name}` is not a valid argument name"),
+            ));
+        }
+        if names.contains(&name) {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("duplicate argument name `{name}`"),
+            ));
+        }
+
+        let arg = if options.nested {
+            quote!(#krate::Arg::default().with_fields(&self.#ident))
+        } else {
+            quote!(#krate::_formatx_arg!(self.#ident))
+        };
+        arms.push(quote!(#name => ::core::option::Option::Some(#arg),));
+        names.push(name);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::ArgSource for #ident #ty_generics #where_clause {
+            fn get_named(&self, name: &str) -> ::core::option::Option<#krate::Arg<'_>> {
+                match name {
+                    #(#arms)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        impl #impl_generics #krate::FormatArgs for #ident #ty_generics #where_clause {
+            const FIELDS: &'static [&'static str] = &[#(#names),*];
+        }
+    })
+}
+
+fn field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident("formatx") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                options.rename = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("nested") {
+                options.nested = true;
+            } else {
+                return Err(meta.error("unknown `formatx` attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// The path to `formatx` from `#[formatx(crate = "...")]` on the struct, or
+/// `::formatx`.
+fn crate_path(input: &DeriveInput) -> syn::Result<syn::Path> {
+    let mut path = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("formatx") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let value: LitStr = meta.value()?.parse()?;
+                path = Some(value.parse()?);
+            } else {
+                return Err(meta.error("unknown `formatx` attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(path.unwrap_or_else(|| syn::parse_quote!(::formatx)))
+}
+
+/// Returns `true` if templates can refer to `name`, i.e. it's an ASCII
+/// identifier as accepted by the `formatx` parser.
+fn is_argument_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    bytes
+        .next()
+        .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
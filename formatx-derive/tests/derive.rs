@@ -0,0 +1,116 @@
+use formatx::{Error, FormatArgs, Template};
+
+#[derive(FormatArgs)]
+struct Customer {
+    name: String,
+    #[formatx(rename = "mail")]
+    email: &'static str,
+}
+
+#[derive(FormatArgs)]
+struct Order {
+    id: u32,
+    total: f64,
+    #[formatx(nested)]
+    customer: Customer,
+    #[formatx(skip)]
+    #[allow(dead_code)]
+    internal: Vec<u8>,
+}
+
+fn order() -> Order {
+    Order {
+        id: 255,
+        total: 12.5,
+        customer: Customer {
+            name: "Alice".into(),
+            email: "alice@example.com",
+        },
+        internal: vec![1, 2, 3],
+    }
+}
+
+#[test]
+fn fields() {
+    assert_eq!(Customer::FIELDS, ["name", "mail"]);
+    assert_eq!(Order::FIELDS, ["id", "total", "customer"]);
+}
+
+#[test]
+fn render_fields() {
+    let t = Template::new("#{id:05} ({id:#x}) {total:.2} for {customer.name} <{customer.mail}>")
+        .unwrap();
+    assert_eq!(
+        t.render().source(&order()).finish().unwrap(),
+        "#00255 (0xff) 12.50 for Alice <alice@example.com>"
+    );
+}
+
+#[test]
+fn skipped_and_renamed() {
+    let order = order();
+    let t = Template::new("{internal}").unwrap();
+    assert!(matches!(
+        t.render().source(&order).finish().unwrap_err(),
+        Error::MissingArgument { ref name, .. } if name == "internal"
+    ));
+    let t = Template::new("{customer.email}").unwrap();
+    assert!(t.render().source(&order).finish().is_err());
+}
+
+#[test]
+fn validate() {
+    assert!(
+        Template::new("{id} {total:.prec$} {customer.name}")
+            .unwrap()
+            .validate::<Order>()
+            .is_err()
+    );
+    assert!(
+        Template::new("{id:>id$} {customer.name} {0}")
+            .unwrap()
+            .validate::<Order>()
+            .is_ok()
+    );
+    let err = Template::new("{id} {missing}")
+        .unwrap()
+        .validate::<Order>()
+        .unwrap_err();
+    assert!(matches!(err, Error::MissingArgument { ref name, .. } if name == "missing"));
+}
+
+#[test]
+fn generic_struct() {
+    #[derive(FormatArgs)]
+    struct Pair<T: std::fmt::Display + std::fmt::Debug> {
+        left: T,
+        right: T,
+    }
+
+    let t = Template::new("{left}-{right:?}").unwrap();
+    let pair = Pair {
+        left: "a",
+        right: "b",
+    };
+    assert_eq!(t.render().source(&pair).finish().unwrap(), "a-\"b\"");
+}
+
+mod renamed_crate {
+    use formatx as fx;
+
+    #[derive(fx::FormatArgs)]
+    #[formatx(crate = "fx")]
+    struct Point {
+        x: i32,
+        #[formatx(rename = "_y2")]
+        y: i32,
+    }
+
+    #[test]
+    fn crate_path() {
+        let t = fx::Template::new("({x}, {_y2:#x})").unwrap();
+        let point = Point { x: 1, y: 255 };
+        assert_eq!(t.render().source(&point).finish().unwrap(), "(1, 0xff)");
+        assert_eq!(<Point as fx::FormatArgs>::FIELDS, ["x", "_y2"]);
+    }
+}
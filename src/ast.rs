@@ -1,4 +1,39 @@
 //! Typed AST for parsed format strings.
+//!
+//! Obtain the AST of a [`Template`](crate::Template) with
+//! [`Template::parsed`](crate::Template::parsed), or walk it with a
+//! [`Visitor`] through [`Template::visit`](crate::Template::visit). Names,
+//! fields and literal text are stored as [`Span`]s into the template source;
+//! [`Template::resolve`](crate::Template::resolve) turns a span back into text
+//! and the [`Visitor`] methods receive the resolved strings directly.
+//!
+//! Enums and structs that may grow in future versions are `#[non_exhaustive]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use formatx::{Template, ast::{Argument, Segment}};
+//!
+//! let template = Template::new("Hello {name}!").unwrap();
+//! let names: Vec<&str> = template
+//!     .parsed()
+//!     .segments
+//!     .iter()
+//!     .filter_map(|segment| match segment {
+//!         Segment::Placeholder(p) => match &p.argument {
+//!             Argument::Named(name) => Some(template.resolve(name.span)),
+//!             _ => None,
+//!         },
+//!         _ => None,
+//!     })
+//!     .collect();
+//! assert_eq!(names, ["name"]);
+//! ```
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 /// Byte range in the source format string.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -9,30 +44,72 @@ pub struct Span {
     pub end: usize,
 }
 
+impl Span {
+    /// The text this span covers in `source`.
+    #[inline]
+    pub(crate) fn slice(self, source: &str) -> &str {
+        &source[self.start..self.end]
+    }
+}
+
 /// A parsed format string, split into segments.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct FormatString {
+    /// The segments in source order.
     pub segments: Vec<Segment>,
+    /// Distinct argument names in order of first use, indexed by [`Name::slot`].
+    pub names: Vec<Span>,
+    /// Unique per parse, so [`Slot`](crate::Slot)s can tell templates apart.
+    pub(crate) id: u64,
+}
+
+impl FormatString {
+    pub(crate) fn new(segments: Vec<Segment>, names: Vec<Span>) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self {
+            segments,
+            names,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl fmt::Debug for FormatString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FormatString")
+            .field("segments", &self.segments)
+            .field("names", &self.names)
+            .finish()
+    }
 }
 
 /// A single piece of a format string.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
+// Placeholders are stored inline to avoid an allocation per placeholder.
+#[allow(clippy::large_enum_variant)]
 pub enum Segment {
     /// Literal text - byte range into the source.
     Literal(Span),
-    /// Escaped open brace `{{` -> `{`.
-    EscapedOpen,
-    /// Escaped close brace `}}` -> `}`.
-    EscapedClose,
+    /// Escaped open brace `{{` -> `{`, with the span of the `{{`.
+    EscapedOpen(Span),
+    /// Escaped close brace `}}` -> `}`, with the span of the `}}`.
+    EscapedClose(Span),
     /// A `{...}` placeholder.
     Placeholder(Placeholder),
 }
 
 /// A single `{...}` placeholder with its argument reference and format spec.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Placeholder {
     /// Which argument this placeholder refers to.
     pub argument: Argument,
+    /// Field and index accesses into the argument, e.g. `.name` and `[0]`.
+    pub path: Vec<PathSegment>,
+    /// Unescaped default text after `=`, used when the argument is missing.
+    pub default: Option<String>,
     /// The format specification after the `:`.
     pub spec: FormatSpec,
     /// Byte span of the entire `{...}` in the source.
@@ -41,25 +118,59 @@ pub struct Placeholder {
 
 /// How a placeholder references its argument.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Argument {
     /// `{}` - uses the next implicit positional index.
     Implicit,
     /// `{0}`, `{1}` - explicit positional index.
     Positional(usize),
-    /// `{name}` - named argument, stored as byte range.
-    Named(Span),
+    /// `{name}` - named argument.
+    Named(Name),
+}
+
+/// A reference to a named argument.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Name {
+    /// Byte range of the name in the source.
+    pub span: Span,
+    /// Index into [`FormatString::names`], shared by every use of the same name.
+    pub slot: usize,
+}
+
+/// A single field or index access in a placeholder path.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum PathSegment {
+    /// `.name` - field access, stored as byte range.
+    Field(Span),
+    /// `[0]` - index access.
+    Index(usize),
 }
 
 /// The full format specification after `:` inside a placeholder.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct FormatSpec {
+    /// Fill character, e.g. `*` in `{:*^10}`.
     pub fill: Option<char>,
+    /// Alignment, e.g. `^` in `{:*^10}`.
     pub align: Option<Align>,
+    /// Sign flag, `+` or `-`.
     pub sign: Option<Sign>,
+    /// Alternate flag `#`.
     pub alternate: bool,
+    /// Zero-padding flag `0`.
     pub zero_pad: bool,
+    /// Minimum width.
     pub width: Option<Count>,
+    /// Byte range of the width, e.g. `w$` in `{:>w$}`.
+    pub width_span: Option<Span>,
+    /// Precision or maximum width.
     pub precision: Option<Precision>,
+    /// Byte range of the precision including the `.`, e.g. `.*` in `{:.*}`.
+    pub precision_span: Option<Span>,
+    /// The formatting trait, e.g. `Debug` for `?`.
     pub format_type: FormatType,
 }
 
@@ -73,7 +184,9 @@ impl FormatSpec {
             alternate: false,
             zero_pad: false,
             width: None,
+            width_span: None,
             precision: None,
+            precision_span: None,
             format_type: FormatType::Display,
         }
     }
@@ -95,20 +208,47 @@ impl FormatSpec {
 /// Text alignment.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Align {
+    /// `<`
     Left,
+    /// `^`
     Center,
+    /// `>`
     Right,
 }
 
+impl Align {
+    /// The spec character, e.g. `<`.
+    pub(crate) fn as_char(self) -> char {
+        match self {
+            Self::Left => '<',
+            Self::Center => '^',
+            Self::Right => '>',
+        }
+    }
+}
+
 /// Sign display mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Sign {
+    /// `+`
     Plus,
+    /// `-`
     Minus,
 }
 
+impl Sign {
+    /// The spec character, e.g. `+`.
+    pub(crate) fn as_char(self) -> char {
+        match self {
+            Self::Plus => '+',
+            Self::Minus => '-',
+        }
+    }
+}
+
 /// A width or precision value - either a literal number or a parameter reference.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Count {
     /// A literal integer, e.g. `10` in `{:10}`.
     Literal(usize),
@@ -118,15 +258,17 @@ pub enum Count {
 
 /// A parameter reference for width/precision.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum CountParam {
     /// `{:0$}` - positional argument index.
     Positional(usize),
-    /// `{:width$}` - named argument, stored as byte range.
-    Named(Span),
+    /// `{:width$}` - named argument.
+    Named(Name),
 }
 
 /// Precision specification.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Precision {
     /// `.5` or `.prec$` - a count value.
     Count(Count),
@@ -135,9 +277,11 @@ pub enum Precision {
 }
 
 /// The format trait to use.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
 pub enum FormatType {
     /// `{}` -`Display`
+    #[default]
     Display,
     /// `{:?}` -`Debug`
     Debug,
@@ -145,7 +289,6 @@ pub enum FormatType {
     DebugLowerHex,
     /// `{:X?}` -`Debug` with uppercase hex integers
     DebugUpperHex,
-    // Parsed but rejected at format time:
     /// `{:o}`
     Octal,
     /// `{:x}`
@@ -161,3 +304,133 @@ pub enum FormatType {
     /// `{:p}`
     Pointer,
 }
+
+impl FormatType {
+    /// The spec suffix, e.g. `x?` for [`FormatType::DebugLowerHex`].
+    pub(crate) fn suffix(self) -> &'static str {
+        match self {
+            Self::Display => "",
+            Self::Debug => "?",
+            Self::DebugLowerHex => "x?",
+            Self::DebugUpperHex => "X?",
+            Self::Octal => "o",
+            Self::LowerHex => "x",
+            Self::UpperHex => "X",
+            Self::Binary => "b",
+            Self::LowerExp => "e",
+            Self::UpperExp => "E",
+            Self::Pointer => "p",
+        }
+    }
+}
+
+/// Walks the segments of a parsed format string.
+///
+/// Every method has a default implementation, so implementors only override
+/// the parts they care about. [`visit_placeholder`](Self::visit_placeholder)
+/// defaults to [`walk_placeholder`], which visits the placeholder's argument,
+/// path, spec and `$` parameters in source order.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{Template, ast::{Argument, Visitor}};
+///
+/// #[derive(Default)]
+/// struct Names(Vec<String>);
+///
+/// impl Visitor for Names {
+///     fn visit_argument(&mut self, _argument: &Argument, name: Option<&str>) {
+///         self.0.extend(name.map(String::from));
+///     }
+/// }
+///
+/// let template = Template::new("{{{greeting}}}, {name:>8} {0}").unwrap();
+/// let mut names = Names::default();
+/// template.visit(&mut names);
+/// assert_eq!(names.0, ["greeting", "name"]);
+/// ```
+pub trait Visitor {
+    /// Literal text between placeholders.
+    fn visit_literal(&mut self, text: &str, span: Span) {
+        let _ = (text, span);
+    }
+
+    /// An escaped `{{` or `}}`, with the unescaped brace.
+    fn visit_escaped(&mut self, brace: char, span: Span) {
+        let _ = (brace, span);
+    }
+
+    /// A `{...}` placeholder. `source` is the whole template source.
+    fn visit_placeholder(&mut self, placeholder: &Placeholder, source: &str) {
+        walk_placeholder(self, placeholder, source);
+    }
+
+    /// The argument of a placeholder, with its name if it is named.
+    fn visit_argument(&mut self, argument: &Argument, name: Option<&str>) {
+        let _ = (argument, name);
+    }
+
+    /// A path segment of a placeholder, with the field name for field accesses.
+    fn visit_path_segment(&mut self, segment: &PathSegment, field: Option<&str>) {
+        let _ = (segment, field);
+    }
+
+    /// The format spec of a placeholder.
+    fn visit_spec(&mut self, spec: &FormatSpec) {
+        let _ = spec;
+    }
+
+    /// A `$` width or precision parameter, with its name if it is named.
+    fn visit_count_param(&mut self, param: &CountParam, name: Option<&str>) {
+        let _ = (param, name);
+    }
+}
+
+/// Visit every segment of `parsed` in source order.
+pub fn walk<V: Visitor + ?Sized>(visitor: &mut V, parsed: &FormatString, source: &str) {
+    for segment in &parsed.segments {
+        match segment {
+            Segment::Literal(span) => visitor.visit_literal(span.slice(source), *span),
+            Segment::EscapedOpen(span) => visitor.visit_escaped('{', *span),
+            Segment::EscapedClose(span) => visitor.visit_escaped('}', *span),
+            Segment::Placeholder(placeholder) => visitor.visit_placeholder(placeholder, source),
+        }
+    }
+}
+
+/// Visit the argument, path segments, spec and `$` parameters of a placeholder.
+pub fn walk_placeholder<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    placeholder: &Placeholder,
+    source: &str,
+) {
+    let name = match &placeholder.argument {
+        Argument::Named(name) => Some(name.span.slice(source)),
+        _ => None,
+    };
+    visitor.visit_argument(&placeholder.argument, name);
+    for segment in &placeholder.path {
+        let field = match segment {
+            PathSegment::Field(span) => Some(span.slice(source)),
+            PathSegment::Index(_) => None,
+        };
+        visitor.visit_path_segment(segment, field);
+    }
+
+    let spec = &placeholder.spec;
+    visitor.visit_spec(spec);
+    let precision = match &spec.precision {
+        Some(Precision::Count(count)) => Some(count),
+        _ => None,
+    };
+    for count in [spec.width.as_ref(), precision].into_iter().flatten() {
+        if let Count::Param(param) = count {
+            let name = match param {
+                CountParam::Named(name) => Some(name.span.slice(source)),
+                CountParam::Positional(_) => None,
+            };
+            visitor.visit_count_param(param, name);
+        }
+    }
+}
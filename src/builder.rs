@@ -0,0 +1,388 @@
+//! [`TemplateBuilder`] - build a [`Template`] from code without writing or
+//! escaping a format string.
+
+use crate::{
+    ast::{
+        Align, Argument, Count, CountParam, FormatSpec, FormatString, FormatType, Name,
+        Placeholder, Precision, Segment, Sign, Span,
+    },
+    parser,
+    template::Template,
+};
+use std::fmt::Write;
+
+/// Builds a [`Template`] from literals and placeholders.
+///
+/// Literal text is escaped automatically, and the template's
+/// [`source`](Template::source) is the equivalent format string, which parses
+/// back to the same template.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{Spec, Template, ast::Align};
+///
+/// let template = Template::builder()
+///     .literal("{id} ")
+///     .named("name", Spec::new().with_fill('.', Align::Left).with_width(10))
+///     .literal(" ")
+///     .named("price", Spec::new().with_precision(2))
+///     .build();
+///
+/// assert_eq!(template.source(), "{{id}} {name:.<10} {price:.2}");
+/// let result = template.render()
+///     .named("name", &"apple")
+///     .named("price", &1.5)
+///     .finish()
+///     .unwrap();
+/// assert_eq!(result, "{id} apple..... 1.50");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TemplateBuilder {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl TemplateBuilder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append literal text. `{` and `}` are escaped.
+    pub fn literal(mut self, text: &str) -> Self {
+        for part in text.split_inclusive(['{', '}']) {
+            let (plain, brace) = match part.char_indices().next_back() {
+                Some((at, c @ ('{' | '}'))) => (&part[..at], Some(c)),
+                _ => (part, None),
+            };
+            if !plain.is_empty() {
+                let start = self.source.len();
+                self.source.push_str(plain);
+                let end = self.source.len();
+                match self.segments.last_mut() {
+                    Some(Segment::Literal(span)) if span.end == start => span.end = end,
+                    _ => self.segments.push(Segment::Literal(Span { start, end })),
+                }
+            }
+            if let Some(brace) = brace {
+                let start = self.source.len();
+                self.source.push(brace);
+                self.source.push(brace);
+                let span = Span {
+                    start,
+                    end: start + 2,
+                };
+                self.segments.push(match brace {
+                    '{' => Segment::EscapedOpen(span),
+                    _ => Segment::EscapedClose(span),
+                });
+            }
+        }
+        self
+    }
+
+    /// Append an implicit positional placeholder, e.g. `{:>5}`.
+    pub fn next(self, spec: Spec) -> Self {
+        self.placeholder(Argument::Implicit, "", spec)
+    }
+
+    /// Append an explicit positional placeholder, e.g. `{0:>5}`.
+    pub fn positional(self, index: usize, spec: Spec) -> Self {
+        self.placeholder(Argument::Positional(index), "", spec)
+    }
+
+    /// Append a named placeholder, e.g. `{name:>5}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid argument name: an ASCII letter or `_`
+    /// followed by ASCII letters, digits or `_`.
+    pub fn named(self, name: &str, spec: Spec) -> Self {
+        assert_name(name);
+        let name_ref = Name {
+            span: Span { start: 0, end: 0 },
+            slot: 0,
+        };
+        self.placeholder(Argument::Named(name_ref), name, spec)
+    }
+
+    /// Finish building the template.
+    pub fn build(mut self) -> Template {
+        let names = parser::intern_names(&self.source, &mut self.segments);
+        Template::from_parts(self.source, FormatString::new(self.segments, names))
+    }
+
+    /// Append a placeholder; `name` is the text of an [`Argument::Named`].
+    fn placeholder(mut self, mut argument: Argument, name: &str, spec: Spec) -> Self {
+        let start = self.source.len();
+        self.source.push('{');
+        match &mut argument {
+            Argument::Implicit => {}
+            Argument::Positional(index) => {
+                let _ = write!(self.source, "{index}");
+            }
+            Argument::Named(name_ref) => name_ref.span = self.push_name(name),
+        }
+
+        let mut ast = FormatSpec::default();
+        ast.format_type = spec.format_type;
+        if !spec.is_default() {
+            self.source.push(':');
+            if let Some(align) = spec.align {
+                self.source.extend(spec.fill);
+                self.source.push(align.as_char());
+                ast.fill = spec.fill;
+                ast.align = Some(align);
+            }
+            if let Some(sign) = spec.sign {
+                self.source.push(sign.as_char());
+                ast.sign = Some(sign);
+            }
+            if spec.alternate {
+                self.source.push('#');
+                ast.alternate = true;
+            }
+            if spec.zero_pad {
+                self.source.push('0');
+                ast.zero_pad = true;
+            }
+            let width_start = self.source.len();
+            ast.width = match spec.width {
+                // A bare `0` width would parse as the zero-pad flag.
+                Some(SpecCount::Literal(0)) if !spec.zero_pad => None,
+                Some(width) => Some(self.push_count(width)),
+                None => None,
+            };
+            if ast.width.is_some() {
+                ast.width_span = Some(Span {
+                    start: width_start,
+                    end: self.source.len(),
+                });
+            }
+            let precision_start = self.source.len();
+            ast.precision = match spec.precision {
+                Some(SpecPrecision::Count(count)) => {
+                    self.source.push('.');
+                    Some(Precision::Count(self.push_count(count)))
+                }
+                Some(SpecPrecision::Star) => {
+                    self.source.push_str(".*");
+                    Some(Precision::Star)
+                }
+                None => None,
+            };
+            if ast.precision.is_some() {
+                ast.precision_span = Some(Span {
+                    start: precision_start,
+                    end: self.source.len(),
+                });
+            }
+            self.source.push_str(spec.format_type.suffix());
+        }
+        self.source.push('}');
+
+        self.segments.push(Segment::Placeholder(Placeholder {
+            argument,
+            path: Vec::new(),
+            default: None,
+            spec: ast,
+            span: Span {
+                start,
+                end: self.source.len(),
+            },
+        }));
+        self
+    }
+
+    fn push_count(&mut self, count: SpecCount) -> Count {
+        match count {
+            SpecCount::Literal(value) => {
+                let _ = write!(self.source, "{value}");
+                Count::Literal(value)
+            }
+            SpecCount::Positional(index) => {
+                let _ = write!(self.source, "{index}$");
+                Count::Param(CountParam::Positional(index))
+            }
+            SpecCount::Named(name) => {
+                let span = self.push_name(&name);
+                self.source.push('$');
+                Count::Param(CountParam::Named(Name { span, slot: 0 }))
+            }
+        }
+    }
+
+    fn push_name(&mut self, name: &str) -> Span {
+        let start = self.source.len();
+        self.source.push_str(name);
+        Span {
+            start,
+            end: self.source.len(),
+        }
+    }
+}
+
+/// The format spec of a placeholder added with [`TemplateBuilder`].
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{Spec, Template, ast::{Align, Sign}, FormatType};
+///
+/// let spec = Spec::new()
+///     .with_fill('*', Align::Center)
+///     .with_sign(Sign::Plus)
+///     .with_width_named("w")
+///     .with_precision(1)
+///     .with_format_type(FormatType::LowerExp);
+/// let template = Template::builder().next(spec).build();
+/// assert_eq!(template.source(), "{:*^+w$.1e}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Spec {
+    fill: Option<char>,
+    align: Option<Align>,
+    sign: Option<Sign>,
+    alternate: bool,
+    zero_pad: bool,
+    width: Option<SpecCount>,
+    precision: Option<SpecPrecision>,
+    format_type: FormatType,
+}
+
+#[derive(Debug, Clone)]
+enum SpecCount {
+    Literal(usize),
+    Positional(usize),
+    Named(String),
+}
+
+#[derive(Debug, Clone)]
+enum SpecPrecision {
+    Count(SpecCount),
+    Star,
+}
+
+impl Spec {
+    /// Create an empty spec, formatting with [`Display`](std::fmt::Display).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the alignment, e.g. `{:>}`.
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    /// Set the fill character and alignment, e.g. `{:*>}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fill` is `{` or `}`, which [`std::fmt`] does not allow.
+    pub fn with_fill(mut self, fill: char, align: Align) -> Self {
+        assert!(
+            fill != '{' && fill != '}',
+            "invalid fill character: `{fill}`"
+        );
+        self.fill = Some(fill);
+        self.align = Some(align);
+        self
+    }
+
+    /// Set the sign flag, e.g. `{:+}`.
+    pub fn with_sign(mut self, sign: Sign) -> Self {
+        self.sign = Some(sign);
+        self
+    }
+
+    /// Set the alternate flag `#`.
+    pub fn with_alternate(mut self, alternate: bool) -> Self {
+        self.alternate = alternate;
+        self
+    }
+
+    /// Set the zero-padding flag `0`.
+    pub fn with_zero_pad(mut self, zero_pad: bool) -> Self {
+        self.zero_pad = zero_pad;
+        self
+    }
+
+    /// Set a literal width, e.g. `{:10}`.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(SpecCount::Literal(width));
+        self
+    }
+
+    /// Take the width from a positional argument, e.g. `{:1$}`.
+    pub fn with_width_arg(mut self, index: usize) -> Self {
+        self.width = Some(SpecCount::Positional(index));
+        self
+    }
+
+    /// Take the width from a named argument, e.g. `{:width$}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid argument name.
+    pub fn with_width_named(mut self, name: &str) -> Self {
+        assert_name(name);
+        self.width = Some(SpecCount::Named(name.to_string()));
+        self
+    }
+
+    /// Set a literal precision, e.g. `{:.3}`.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = Some(SpecPrecision::Count(SpecCount::Literal(precision)));
+        self
+    }
+
+    /// Take the precision from a positional argument, e.g. `{:.1$}`.
+    pub fn with_precision_arg(mut self, index: usize) -> Self {
+        self.precision = Some(SpecPrecision::Count(SpecCount::Positional(index)));
+        self
+    }
+
+    /// Take the precision from a named argument, e.g. `{:.prec$}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid argument name.
+    pub fn with_precision_named(mut self, name: &str) -> Self {
+        assert_name(name);
+        self.precision = Some(SpecPrecision::Count(SpecCount::Named(name.to_string())));
+        self
+    }
+
+    /// Take the precision from the next implicit positional argument, `{:.*}`.
+    pub fn with_star_precision(mut self) -> Self {
+        self.precision = Some(SpecPrecision::Star);
+        self
+    }
+
+    /// Set the formatting trait, e.g. [`FormatType::Debug`] for `{:?}`.
+    pub fn with_format_type(mut self, format_type: FormatType) -> Self {
+        self.format_type = format_type;
+        self
+    }
+
+    fn is_default(&self) -> bool {
+        self.align.is_none()
+            && self.sign.is_none()
+            && !self.alternate
+            && !self.zero_pad
+            && self.width.is_none()
+            && self.precision.is_none()
+            && self.format_type == FormatType::Display
+    }
+}
+
+fn assert_name(name: &str) {
+    let mut bytes = name.bytes();
+    let valid = bytes
+        .next()
+        .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_');
+    assert!(valid, "invalid argument name: `{name}`");
+}
@@ -0,0 +1,185 @@
+//! The global [`TemplateCache`] - a bounded LRU cache of parsed templates.
+
+use crate::{ast::FormatString, error::Error, parser};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Arc, LazyLock, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static ENTRIES: LazyLock<Mutex<Entries>> = LazyLock::new(|| Mutex::new(Entries::default()));
+
+/// Process-wide, thread-safe LRU cache of parsed templates keyed by their
+/// source string.
+///
+/// Used by [`formatx!`](crate::formatx), [`formatxl!`](crate::formatxl) and
+/// [`Template::cached`](crate::Template::cached) to avoid re-parsing the same
+/// template on hot paths. The cache is **disabled** (capacity `0`) until
+/// [`resize`](Self::resize) is called with a non-zero capacity.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{TemplateCache, formatx};
+///
+/// TemplateCache::resize(128);
+/// for i in 0..3 {
+///     formatx!("{} items", i).unwrap();
+/// }
+/// let stats = TemplateCache::stats();
+/// assert!(stats.hits >= 2);
+/// ```
+pub struct TemplateCache;
+
+impl TemplateCache {
+    /// Set the maximum number of cached templates, evicting the least
+    /// recently used entries if the cache is over the new capacity.
+    ///
+    /// A capacity of `0` disables the cache and drops every entry.
+    pub fn resize(capacity: usize) {
+        let mut entries = entries();
+        CAPACITY.store(capacity, Ordering::Relaxed);
+        while entries.map.len() > capacity {
+            entries.evict();
+        }
+    }
+
+    /// Returns the maximum number of cached templates.
+    pub fn capacity() -> usize {
+        CAPACITY.load(Ordering::Relaxed)
+    }
+
+    /// Remove every cached template and reset the hit/miss counters.
+    pub fn clear() {
+        let mut entries = entries();
+        entries.map.clear();
+        entries.order.clear();
+        HITS.store(0, Ordering::Relaxed);
+        MISSES.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the cache statistics.
+    pub fn stats() -> CacheStats {
+        CacheStats {
+            hits: HITS.load(Ordering::Relaxed),
+            misses: MISSES.load(Ordering::Relaxed),
+            len: entries().map.len(),
+            capacity: Self::capacity(),
+        }
+    }
+
+    /// Look up `source` in the cache, parsing and inserting it on a miss.
+    ///
+    /// The template is parsed without holding the lock, so a miss doesn't
+    /// block other threads. Parse errors are returned without caching
+    /// anything.
+    pub(crate) fn get(source: &str) -> Result<(Arc<str>, Arc<FormatString>), Error> {
+        if Self::capacity() == 0 {
+            return Ok((source.into(), Arc::new(parser::parse(source)?)));
+        }
+
+        if let Some(hit) = entries().touch(source) {
+            HITS.fetch_add(1, Ordering::Relaxed);
+            return Ok(hit);
+        }
+        MISSES.fetch_add(1, Ordering::Relaxed);
+        let parsed = Arc::new(parser::parse(source)?);
+
+        let mut entries = entries();
+        // Another thread may have cached the same source while we parsed;
+        // share its template so slots work across both.
+        if let Some(hit) = entries.touch(source) {
+            return Ok(hit);
+        }
+        let source: Arc<str> = source.into();
+        // Re-read under the lock: `resize` may have run since the check above.
+        let capacity = Self::capacity();
+        if capacity > 0 {
+            while entries.map.len() >= capacity {
+                entries.evict();
+            }
+            entries.insert(source.clone(), parsed.clone());
+        }
+        Ok((source, parsed))
+    }
+}
+
+/// A snapshot of [`TemplateCache`] statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of lookups that found a cached template.
+    pub hits: u64,
+    /// Number of lookups that had to parse the template.
+    pub misses: u64,
+    /// Number of templates currently cached.
+    pub len: usize,
+    /// Maximum number of templates the cache holds.
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups that were hits, between `0.0` and `1.0`.
+    ///
+    /// Returns `0.0` if there were no lookups.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<Arc<str>, Entry>,
+    /// Sources by the tick they were last used at, oldest first.
+    order: BTreeMap<u64, Arc<str>>,
+    tick: u64,
+}
+
+struct Entry {
+    source: Arc<str>,
+    parsed: Arc<FormatString>,
+    last_used: u64,
+}
+
+impl Entries {
+    /// Mark `source` as the most recently used entry and return it, if cached.
+    fn touch(&mut self, source: &str) -> Option<(Arc<str>, Arc<FormatString>)> {
+        self.tick += 1;
+        let entry = self.map.get_mut(source)?;
+        let key = self.order.remove(&entry.last_used)?;
+        entry.last_used = self.tick;
+        self.order.insert(self.tick, key);
+        Some((entry.source.clone(), entry.parsed.clone()))
+    }
+
+    fn insert(&mut self, source: Arc<str>, parsed: Arc<FormatString>) {
+        self.tick += 1;
+        self.order.insert(self.tick, source.clone());
+        let entry = Entry {
+            source: source.clone(),
+            parsed,
+            last_used: self.tick,
+        };
+        self.map.insert(source, entry);
+    }
+
+    /// Remove the least recently used entry.
+    fn evict(&mut self) {
+        if let Some((_, key)) = self.order.pop_first() {
+            self.map.remove(&key);
+        }
+    }
+}
+
+fn entries() -> MutexGuard<'static, Entries> {
+    ENTRIES.lock().unwrap_or_else(PoisonError::into_inner)
+}
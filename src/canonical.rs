@@ -0,0 +1,142 @@
+//! Canonical re-serialization of a parsed template, see
+//! [`Template::to_canonical_string`](crate::Template::to_canonical_string).
+
+use crate::ast::{
+    Argument, Count, CountParam, FormatSpec, FormatString, PathSegment, Precision, Segment, Sign,
+};
+use std::fmt::Write;
+
+/// Print `parsed` in canonical form:
+///
+/// - literal text is merged and `{`/`}` escaped as `{{`/`}}`,
+/// - implicit `{}` and `.*` become explicit positional indices,
+/// - flags without effect are dropped: fill, alignment and zero-padding
+///   without a width, a zero width, the default `' '` fill and the `-` sign,
+/// - an empty spec is dropped, including the `:`.
+pub(crate) fn to_canonical_string(source: &str, parsed: &FormatString) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut implicit = 0;
+
+    for segment in &parsed.segments {
+        let placeholder = match segment {
+            Segment::Literal(span) => {
+                for c in span.slice(source).chars() {
+                    if matches!(c, '{' | '}') {
+                        out.push(c);
+                    }
+                    out.push(c);
+                }
+                continue;
+            }
+            Segment::EscapedOpen(_) => {
+                out.push_str("{{");
+                continue;
+            }
+            Segment::EscapedClose(_) => {
+                out.push_str("}}");
+                continue;
+            }
+            Segment::Placeholder(placeholder) => placeholder,
+        };
+
+        // `.*` takes the next implicit index before the value does.
+        let star = match placeholder.spec.precision {
+            Some(Precision::Star) => {
+                implicit += 1;
+                Some(implicit - 1)
+            }
+            _ => None,
+        };
+
+        out.push('{');
+        match &placeholder.argument {
+            Argument::Implicit => {
+                let _ = write!(out, "{implicit}");
+                implicit += 1;
+            }
+            Argument::Positional(index) => {
+                let _ = write!(out, "{index}");
+            }
+            Argument::Named(name) => out.push_str(name.span.slice(source)),
+        }
+        for segment in &placeholder.path {
+            match segment {
+                PathSegment::Field(span) => {
+                    out.push('.');
+                    out.push_str(span.slice(source));
+                }
+                PathSegment::Index(index) => {
+                    let _ = write!(out, "[{index}]");
+                }
+            }
+        }
+        if let Some(default) = &placeholder.default {
+            out.push('=');
+            for c in default.chars() {
+                if matches!(c, '\\' | ':' | '{' | '}') {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+        }
+        write_spec(&mut out, source, &placeholder.spec, star);
+        out.push('}');
+    }
+
+    out
+}
+
+/// Write `:spec` without its redundant parts, or nothing for an empty spec.
+fn write_spec(out: &mut String, source: &str, spec: &FormatSpec, star: Option<usize>) {
+    // A bare `0` width pads nothing, and would parse as the zero-pad flag.
+    let width = spec
+        .width
+        .as_ref()
+        .filter(|width| spec.zero_pad || !matches!(width, Count::Literal(0)));
+
+    let mut canonical = String::new();
+    if width.is_some()
+        && let Some(align) = spec.align
+    {
+        canonical.extend(spec.fill.filter(|&fill| fill != ' '));
+        canonical.push(align.as_char());
+    }
+    if spec.sign == Some(Sign::Plus) {
+        canonical.push('+');
+    }
+    if spec.alternate {
+        canonical.push('#');
+    }
+    if let Some(width) = width {
+        if spec.zero_pad {
+            canonical.push('0');
+        }
+        write_count(&mut canonical, source, width);
+    }
+    match &spec.precision {
+        Some(Precision::Count(count)) => {
+            canonical.push('.');
+            write_count(&mut canonical, source, count);
+        }
+        Some(Precision::Star) => {
+            let _ = write!(canonical, ".{}$", star.unwrap_or_default());
+        }
+        None => {}
+    }
+    canonical.push_str(spec.format_type.suffix());
+
+    if !canonical.is_empty() {
+        out.push(':');
+        out.push_str(&canonical);
+    }
+}
+
+fn write_count(out: &mut String, source: &str, count: &Count) {
+    let _ = match count {
+        Count::Literal(value) => write!(out, "{value}"),
+        Count::Param(CountParam::Positional(index)) => write!(out, "{index}$"),
+        Count::Param(CountParam::Named(name)) => {
+            write!(out, "{}$", name.span.slice(source))
+        }
+    };
+}
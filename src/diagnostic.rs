@@ -0,0 +1,147 @@
+//! [`Diagnostic`] - human-readable error reports pointing into the template.
+
+use crate::{
+    ast::Span,
+    error::{Error, ParseErrorKind},
+};
+use std::fmt;
+
+/// An [`Error`] together with the template source it refers to, displayed
+/// with the line and column, the offending line and a caret under the span.
+///
+/// Created with [`Error::diagnostic`]. Errors without a span, such as
+/// [`Error::Format`], display only their message.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::Template;
+///
+/// let source = "Dear {name},\nyour total is {total:.2q}";
+/// let err = Template::new(source).unwrap_err();
+/// assert_eq!(
+///     err.diagnostic(source).to_string(),
+///     "\
+/// error: unknown format type: `q`
+///  --> 2:24
+///   |
+/// 2 | your total is {total:.2q}
+///   |                        ^
+///   = help: expected one of `?`, `x?`, `X?`, `o`, `x`, `X`, `b`, `e`, `E`, `p`
+/// ",
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Diagnostic<'a> {
+    error: &'a Error,
+    source: &'a str,
+    help: Option<String>,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub(crate) fn new(error: &'a Error, source: &'a str) -> Self {
+        Self {
+            error,
+            source,
+            help: default_help(error),
+        }
+    }
+
+    /// Replace the help note printed below the excerpt.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Remove the help note.
+    pub fn without_help(mut self) -> Self {
+        self.help = None;
+        self
+    }
+
+    /// Returns the 1-based line and column (in characters) of the start of
+    /// the error's span, or `None` if the error has no span.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        let span = self.span()?;
+        let before = &self.source[..span.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Some((line, before[line_start..].chars().count() + 1))
+    }
+
+    /// The error's span, if it lies within the source.
+    fn span(&self) -> Option<Span> {
+        self.error.span().filter(|span| {
+            span.start <= span.end
+                && span.end <= self.source.len()
+                && self.source.is_char_boundary(span.start)
+                && self.source.is_char_boundary(span.end)
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.error {
+            Error::Parse { kind, .. } => writeln!(f, "error: {kind}")?,
+            error => writeln!(f, "error: {error}")?,
+        }
+
+        if let (Some(span), Some((line, column))) = (self.span(), self.location()) {
+            let line_start = self.source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = self.source[span.start..]
+                .find('\n')
+                .map_or(self.source.len(), |i| span.start + i);
+            let text = self.source[line_start..line_end].trim_end_matches('\r');
+
+            // Keep tabs so the caret lines up with the excerpt.
+            let indent: String = self.source[line_start..span.start]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let carets = self.source[span.start..span.end.min(line_end)]
+                .chars()
+                .count()
+                .max(1);
+
+            let gutter = " ".repeat(line.to_string().len());
+            writeln!(f, "{gutter}--> {line}:{column}")?;
+            writeln!(f, "{gutter} |")?;
+            writeln!(f, "{line} | {text}")?;
+            writeln!(f, "{gutter} | {indent}{}", "^".repeat(carets))?;
+            if let Some(help) = &self.help {
+                writeln!(f, "{gutter} = help: {help}")?;
+            }
+        } else if let Some(help) = &self.help {
+            writeln!(f, "  = help: {help}")?;
+        }
+        Ok(())
+    }
+}
+
+fn default_help(error: &Error) -> Option<String> {
+    match error {
+        Error::Parse { kind, .. } => match kind {
+            ParseErrorKind::UnmatchedOpen => Some("use `{{` for a literal `{`".to_string()),
+            ParseErrorKind::UnmatchedClose => Some("use `}}` for a literal `}`".to_string()),
+            ParseErrorKind::UnknownFormatType(_) => Some(
+                "expected one of `?`, `x?`, `X?`, `o`, `x`, `X`, `b`, `e`, `E`, `p`".to_string(),
+            ),
+            _ => None,
+        },
+        Error::MissingArgument {
+            name, suggestions, ..
+        } => Some(match suggestions.as_slice() {
+            [] => format!("provide an argument for `{name}`"),
+            [suggestion] => format!("did you mean `{suggestion}`?"),
+            suggestions => format!("did you mean one of `{}`?", suggestions.join("`, `")),
+        }),
+        Error::CountArgument { name, .. } => Some(format!(
+            "provide an integer argument for `{name}` to use as width or precision"
+        )),
+        Error::UnsupportedTrait { .. } => {
+            Some("pass the argument with `Arg` to enable more formatting traits".to_string())
+        }
+        _ => None,
+    }
+}
@@ -1,41 +1,102 @@
 //! Error types for formatx.
 
-use crate::ast::{FormatType, Span};
+use crate::{
+    ast::{FormatType, Span},
+    diagnostic::Diagnostic,
+};
 use std::fmt;
 
 /// Errors that can occur during parsing or formatting.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
     /// The format string could not be parsed.
-    Parse {
-        span: Span,
-        message: String,
-    },
+    Parse { span: Span, kind: ParseErrorKind },
     /// A placeholder references an argument that was not provided.
     MissingArgument {
         name: String,
         span: Span,
+        /// Similar argument names that were provided or used elsewhere in
+        /// the template, closest first.
+        suggestions: Vec<String>,
     },
-    /// A format type (e.g. `{:x}`) requires a trait we don't support.
-    UnsupportedTrait {
-        format_type: FormatType,
+    /// A width or precision argument (`{:w$}`, `{:.1$}` or `{:.*}`) is missing
+    /// or not a valid `usize`.
+    CountArgument {
+        /// The argument's name, or its index for positional and `.*` arguments.
+        name: String,
+        /// The formatted value if the argument was provided but is not a
+        /// valid `usize`; `None` if it is missing.
+        value: Option<String>,
+        /// Span of the width or precision, e.g. `w$` or `.*`.
         span: Span,
+        /// Span of the enclosing placeholder.
+        placeholder: Span,
     },
+    /// A format type (e.g. `{:x}`) requires a trait the argument doesn't support.
+    UnsupportedTrait { format_type: FormatType, span: Span },
     /// An underlying `std::fmt::Error` occurred during formatting.
     Format(fmt::Error),
+    /// A value could not be serialized into a [`Value`](crate::Value).
+    #[cfg(feature = "serde")]
+    Serialize(String),
+    /// A [`Slot`](crate::Slot) was set on a renderer for a different template
+    /// than the one that produced it.
+    ForeignSlot,
+    /// Arguments were provided but not used by any placeholder, with
+    /// [`RenderOptions::with_deny_unused`](crate::RenderOptions::with_deny_unused).
+    UnusedArgument {
+        positional: Vec<usize>,
+        named: Vec<String>,
+    },
+}
+
+impl Error {
+    /// Returns the span in the template source the error refers to, if any.
+    ///
+    /// For [`Error::MissingArgument`] and [`Error::UnsupportedTrait`] this is
+    /// the span of the whole placeholder.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Parse { span, .. }
+            | Self::MissingArgument { span, .. }
+            | Self::UnsupportedTrait { span, .. }
+            | Self::CountArgument { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// Pair the error with the template `source` it came from, to display it
+    /// with the line, column and an excerpt of the source.
+    ///
+    /// See [`Diagnostic`] for an example.
+    pub fn diagnostic<'a>(&'a self, source: &'a str) -> Diagnostic<'a> {
+        Diagnostic::new(self, source)
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Parse { message, span, .. } => {
-                write!(f, "parse error at byte {}: {}", span.start, message)
+            Self::Parse { kind, span } => {
+                write!(f, "parse error at byte {}: {}", span.start, kind)
             }
             Self::MissingArgument { name, .. } => {
                 write!(f, "missing argument: `{name}`")
             }
+            Self::CountArgument {
+                name, value: None, ..
+            } => write!(f, "missing count argument: `{name}`"),
+            Self::CountArgument {
+                name,
+                value: Some(value),
+                ..
+            } => write!(f, "count argument `{name}` is not a valid usize: `{value}`"),
             Self::UnsupportedTrait { format_type, .. } => {
                 let trait_name = match format_type {
+                    FormatType::Display => "Display",
+                    FormatType::Debug | FormatType::DebugLowerHex | FormatType::DebugUpperHex => {
+                        "Debug"
+                    }
                     FormatType::Octal => "Octal",
                     FormatType::LowerHex => "LowerHex",
                     FormatType::UpperHex => "UpperHex",
@@ -43,11 +104,77 @@ impl fmt::Display for Error {
                     FormatType::LowerExp => "LowerExp",
                     FormatType::UpperExp => "UpperExp",
                     FormatType::Pointer => "Pointer",
-                    _ => "Unknown",
                 };
                 write!(f, "unsupported format trait: `{trait_name}`")
             }
             Self::Format(e) => write!(f, "formatting error: {e}"),
+            #[cfg(feature = "serde")]
+            Self::Serialize(message) => write!(f, "serialization error: {message}"),
+            Self::ForeignSlot => f.write_str("slot does not belong to this template"),
+            Self::UnusedArgument { positional, named } => {
+                f.write_str("unused arguments: ")?;
+                let positional = positional.iter().map(|index| index.to_string());
+                let named = named.iter().map(|name| format!("`{name}`"));
+                for (i, arg) in positional.chain(named).enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(&arg)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Why a format string could not be parsed, see [`Error::Parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A `{` without a matching `}`.
+    UnmatchedOpen,
+    /// A `}` without a matching `{`, outside a `}}` escape.
+    UnmatchedClose,
+    /// The format string ends inside a placeholder.
+    UnexpectedEnd,
+    /// A placeholder argument or path contains this character.
+    InvalidArgument(char),
+    /// A `.` in a path is not followed by a field name.
+    ExpectedField,
+    /// A `[` in a path is not followed by an index and `]`.
+    ExpectedIndex,
+    /// A `.` in a format spec is not followed by a precision.
+    ExpectedPrecision,
+    /// An index, width or precision does not fit in a `usize`.
+    InvalidNumber(String),
+    /// This character is not a format type, e.g. `q` in `{:q}`.
+    UnknownFormatType(char),
+    /// A `\` in a default value escapes this character, which is not `\`,
+    /// `:`, `{` or `}`.
+    InvalidEscape(char),
+    /// A default value contains an unescaped `{`.
+    UnescapedBrace,
+    /// A default value is not followed by `:` or `}`.
+    UnterminatedDefault,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnmatchedOpen => f.write_str("unmatched `{`"),
+            Self::UnmatchedClose => f.write_str("unmatched `}`"),
+            Self::UnexpectedEnd => f.write_str("unexpected end of format string"),
+            Self::InvalidArgument(c) => {
+                write!(f, "invalid character `{c}` in placeholder argument")
+            }
+            Self::ExpectedField => f.write_str("expected field name after `.`"),
+            Self::ExpectedIndex => f.write_str("expected index inside `[]`"),
+            Self::ExpectedPrecision => f.write_str("expected precision after `.`"),
+            Self::InvalidNumber(number) => write!(f, "number too large: `{number}`"),
+            Self::UnknownFormatType(c) => write!(f, "unknown format type: `{c}`"),
+            Self::InvalidEscape(c) => write!(f, "invalid escape `\\{c}` in default value"),
+            Self::UnescapedBrace => f.write_str("unescaped `{` in default value"),
+            Self::UnterminatedDefault => f.write_str("unterminated default value"),
         }
     }
 }
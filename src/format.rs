@@ -1,65 +1,104 @@
 //! Format engine - applies [`FormatSpec`] to produce formatted output.
 
-use crate::{ast::*, error::Error, value::FormatValue};
-use std::fmt::{Debug, Write};
+use crate::{
+    ast::*,
+    error::Error,
+    options::{MissingPolicy, RenderOptions},
+    renderer::Renderer,
+    source::ArgSource,
+    suggest,
+    value::{Arg, FormatValue},
+};
+use std::fmt::{self, Debug, Display, Write};
 
 /// Render a parsed [`FormatString`] into `output` using the provided arguments.
 pub fn render(
-    output: &mut String,
+    output: &mut dyn Write,
     source: &str,
     parsed: &FormatString,
-    args: &[&dyn FormatValue],
-    named: &[(&str, usize)],
-    strict: bool,
+    args: &Renderer<'_>,
+    options: &RenderOptions,
 ) -> Result<(), Error> {
     let mut implicit_pos: usize = 0;
 
     for segment in &parsed.segments {
         match segment {
-            Segment::Literal(span) => {
-                output.push_str(&source[span.start..span.end]);
-            }
-            Segment::EscapedOpen => output.push('{'),
-            Segment::EscapedClose => output.push('}'),
+            Segment::Literal(span) => output.write_str(span.slice(source))?,
+            Segment::EscapedOpen(_) => output.write_char('{')?,
+            Segment::EscapedClose(_) => output.write_char('}')?,
             Segment::Placeholder(placeholder) => {
                 // For `.*`, the precision arg is consumed BEFORE the value arg.
                 let resolved_precision = resolve_precision(
                     &placeholder.spec.precision,
+                    placeholder.spec.precision_span,
+                    placeholder,
                     source,
                     args,
-                    named,
                     &mut implicit_pos,
-                )?;
+                );
 
-                // Now resolve the argument index
-                let arg_index =
-                    resolve_argument(&placeholder.argument, source, &mut implicit_pos, named);
+                // Now resolve the argument and its path
+                let arg = resolve_argument(&placeholder.argument, source, &mut implicit_pos, args)
+                    .and_then(|arg| resolve_path(arg, &placeholder.path, source));
+                let resolved_width = resolve_count_value(
+                    &placeholder.spec.width,
+                    placeholder.spec.width_span,
+                    placeholder,
+                    source,
+                    args,
+                );
 
-                let arg = match arg_index {
-                    Some(idx) if idx < args.len() => Some(args[idx]),
-                    Some(_) | None => None,
+                let resolved_precision = match resolved_precision {
+                    Ok(precision) => precision,
+                    Err(CountError::Missing(name, error)) => {
+                        substitute(output, options, source, placeholder, &name, error)?;
+                        continue;
+                    }
+                    Err(CountError::Invalid(error)) => return Err(error),
                 };
 
-                if arg.is_none() {
-                    if strict {
-                        let name = match &placeholder.argument {
-                            Argument::Implicit => format!("{}", implicit_pos - 1),
-                            Argument::Positional(idx) => format!("{idx}"),
-                            Argument::Named(span) => source[span.start..span.end].to_string(),
-                        };
-                        return Err(Error::MissingArgument {
-                            name,
-                            span: placeholder.span,
-                        });
+                let arg = arg.or_else(|| placeholder.default.as_ref().map(Arg::new));
+                let Some(arg) = arg else {
+                    let mut name = match &placeholder.argument {
+                        Argument::Implicit => format!("{}", implicit_pos - 1),
+                        Argument::Positional(idx) => format!("{idx}"),
+                        Argument::Named(name) => name.span.slice(source).to_string(),
+                    };
+                    for segment in &placeholder.path {
+                        match segment {
+                            PathSegment::Field(span) => write!(name, ".{}", span.slice(source))?,
+                            PathSegment::Index(idx) => write!(name, "[{idx}]")?,
+                        }
                     }
+                    // Suggestions only matter if the error is returned.
+                    let suggestions = match (&placeholder.argument, &options.missing) {
+                        (Argument::Named(arg), MissingPolicy::Error)
+                            if placeholder.path.is_empty() =>
+                        {
+                            suggest::suggest(arg.span.slice(source), args.names())
+                        }
+                        _ => Vec::new(),
+                    };
+                    let error = Error::MissingArgument {
+                        name: name.clone(),
+                        span: placeholder.span,
+                        suggestions,
+                    };
+                    substitute(output, options, source, placeholder, &name, error)?;
                     continue;
-                }
+                };
 
-                let arg = arg.unwrap();
-                let resolved_width =
-                    resolve_count_value(&placeholder.spec.width, source, args, named)?;
+                let resolved_width = match resolved_width {
+                    Ok(width) => width,
+                    Err(CountError::Missing(name, error)) => {
+                        substitute(output, options, source, placeholder, &name, error)?;
+                        continue;
+                    }
+                    Err(CountError::Invalid(error)) => return Err(error),
+                };
 
-                check_format_type(placeholder.spec.format_type, placeholder.span)?;
+                check_format_type(placeholder.spec.format_type, &arg, placeholder.span)?;
+                let arg: &dyn FormatValue = &Traited(arg, placeholder.spec.format_type);
 
                 // Fast path: default spec with Display
                 if placeholder.spec.is_default()
@@ -78,7 +117,7 @@ pub fn render(
                 if placeholder.spec.fill.is_some() || placeholder.spec.align.is_some() {
                     let mut buf = String::new();
                     format_core(&mut buf, arg, &placeholder.spec, resolved_precision)?;
-                    apply_padding(output, &buf, &placeholder.spec, resolved_width);
+                    apply_padding(output, &buf, &placeholder.spec, resolved_width)?;
                 } else {
                     // Let std::fmt handle width + native alignment
                     format_full(
@@ -98,108 +137,146 @@ pub fn render(
 
 // Argument/count resolution
 
-fn resolve_argument(
+fn resolve_argument<'s>(
     argument: &Argument,
     source: &str,
     implicit_pos: &mut usize,
-    named: &[(&str, usize)],
-) -> Option<usize> {
+    args: &'s Renderer<'_>,
+) -> Option<Arg<'s>> {
     match argument {
         Argument::Implicit => {
             let idx = *implicit_pos;
             *implicit_pos += 1;
-            Some(idx)
-        }
-        Argument::Positional(idx) => Some(*idx),
-        Argument::Named(span) => {
-            let name = &source[span.start..span.end];
-            named.iter().find(|(n, _)| *n == name).map(|(_, idx)| *idx)
+            args.get_positional(idx)
         }
+        Argument::Positional(idx) => args.get_positional(*idx),
+        Argument::Named(name) => args.get_slot(name.slot, name.span.slice(source)),
     }
 }
 
+fn resolve_path<'s>(arg: Arg<'s>, path: &[PathSegment], source: &str) -> Option<Arg<'s>> {
+    path.iter().try_fold(arg, |arg, segment| match segment {
+        PathSegment::Field(span) => arg.fields?.get_named(span.slice(source)),
+        PathSegment::Index(idx) => arg.fields?.get_positional(*idx),
+    })
+}
+
+/// Why a width or precision argument could not be resolved.
+enum CountError {
+    /// The argument was not provided. Carries its name and the strict-mode error.
+    Missing(String, Error),
+    /// The argument is not a valid count.
+    Invalid(Error),
+}
+
 fn resolve_count_value(
     count: &Option<Count>,
+    span: Option<Span>,
+    placeholder: &Placeholder,
     source: &str,
-    args: &[&dyn FormatValue],
-    named: &[(&str, usize)],
-) -> Result<Option<usize>, Error> {
+    args: &Renderer<'_>,
+) -> Result<Option<usize>, CountError> {
     let Some(count) = count else { return Ok(None) };
-    match count {
-        Count::Literal(n) => Ok(Some(*n)),
-        Count::Param(param) => {
-            let idx = match param {
-                CountParam::Positional(idx) => *idx,
-                CountParam::Named(span) => {
-                    let name = &source[span.start..span.end];
-                    named
-                        .iter()
-                        .find(|(n, _)| *n == name)
-                        .map(|(_, idx)| *idx)
-                        .ok_or_else(|| Error::Parse {
-                            span: *span,
-                            message: format!("missing count argument: `{name}`"),
-                        })?
-                }
-            };
-            if idx >= args.len() {
-                return Err(Error::Parse {
-                    span: Span { start: 0, end: 0 },
-                    message: format!("count argument index {idx} out of range"),
-                });
-            }
-            let formatted = format!("{}", args[idx]);
-            formatted
-                .parse::<usize>()
-                .map(Some)
-                .map_err(|_| Error::Parse {
-                    span: Span { start: 0, end: 0 },
-                    message: format!("count argument `{formatted}` is not a valid usize"),
-                })
-        }
-    }
+    let name = match count {
+        Count::Literal(n) => return Ok(Some(*n)),
+        Count::Param(CountParam::Positional(idx)) => idx.to_string(),
+        Count::Param(CountParam::Named(name)) => name.span.slice(source).to_string(),
+    };
+    let arg = match count {
+        Count::Param(CountParam::Named(name)) => args.get_slot(name.slot, name.span.slice(source)),
+        Count::Param(CountParam::Positional(idx)) => args.get_positional(*idx),
+        Count::Literal(_) => None,
+    };
+    count_value(arg, name, span, placeholder)
 }
 
 fn resolve_precision(
     precision: &Option<Precision>,
+    span: Option<Span>,
+    placeholder: &Placeholder,
     source: &str,
-    args: &[&dyn FormatValue],
-    named: &[(&str, usize)],
+    args: &Renderer<'_>,
     implicit_pos: &mut usize,
-) -> Result<Option<usize>, Error> {
+) -> Result<Option<usize>, CountError> {
     let Some(prec) = precision else {
         return Ok(None);
     };
     match prec {
-        Precision::Count(count) => resolve_count_value(&Some(count.clone()), source, args, named),
+        Precision::Count(count) => {
+            resolve_count_value(&Some(count.clone()), span, placeholder, source, args)
+        }
         Precision::Star => {
             let idx = *implicit_pos;
             *implicit_pos += 1;
-            if idx >= args.len() {
-                return Err(Error::Parse {
-                    span: Span { start: 0, end: 0 },
-                    message: "not enough arguments for `.*` precision".to_string(),
-                });
-            }
-            let formatted = format!("{}", args[idx]);
-            formatted
-                .parse::<usize>()
-                .map(Some)
-                .map_err(|_| Error::Parse {
-                    span: Span { start: 0, end: 0 },
-                    message: format!("`.*` precision argument `{formatted}` is not a valid usize"),
-                })
+            count_value(args.get_positional(idx), idx.to_string(), span, placeholder)
         }
     }
 }
 
-fn check_format_type(format_type: FormatType, span: Span) -> Result<(), Error> {
-    match format_type {
-        FormatType::Display
-        | FormatType::Debug
-        | FormatType::DebugLowerHex
-        | FormatType::DebugUpperHex => Ok(()),
-        _ => Err(Error::UnsupportedTrait { format_type, span }),
+/// Read a width or precision argument `name` as a `usize`.
+fn count_value(
+    arg: Option<Arg<'_>>,
+    name: String,
+    span: Option<Span>,
+    placeholder: &Placeholder,
+) -> Result<Option<usize>, CountError> {
+    let error = |value| Error::CountArgument {
+        name: name.clone(),
+        value,
+        span: span.unwrap_or(placeholder.span),
+        placeholder: placeholder.span,
+    };
+    let Some(arg) = arg else {
+        return Err(CountError::Missing(name.clone(), error(None)));
+    };
+    let formatted = arg.display.map(|v| v.to_string()).unwrap_or_default();
+    match formatted.parse::<usize>() {
+        Ok(value) => Ok(Some(value)),
+        Err(_) => Err(CountError::Invalid(error(Some(formatted)))),
+    }
+}
+
+/// Handle a placeholder whose argument `name` is missing, according to the
+/// [`MissingPolicy`]. `error` is returned under [`MissingPolicy::Error`].
+fn substitute(
+    output: &mut dyn Write,
+    options: &RenderOptions,
+    source: &str,
+    placeholder: &Placeholder,
+    name: &str,
+    error: Error,
+) -> Result<(), Error> {
+    match &options.missing {
+        MissingPolicy::Error => return Err(error),
+        MissingPolicy::Empty => {}
+        MissingPolicy::Keep => output.write_str(placeholder.span.slice(source))?,
+        MissingPolicy::Marker(marker) => output.write_str(marker)?,
+        MissingPolicy::Callback(callback) => output.write_str(&callback(name, placeholder.span))?,
+    }
+    Ok(())
+}
+
+fn check_format_type(format_type: FormatType, arg: &Arg, span: Span) -> Result<(), Error> {
+    if arg.supports(format_type) {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedTrait { format_type, span })
+    }
+}
+
+/// Formats an [`Arg`] through the trait selected by the placeholder, exposed
+/// as `Display + Debug` so the regular width/fill/sign/alternate handling applies.
+struct Traited<'a>(Arg<'a>, FormatType);
+
+impl Display for Traited<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(self.1, f).unwrap_or(Err(fmt::Error))
+    }
+}
+
+impl Debug for Traited<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
     }
 }
 
@@ -211,7 +288,7 @@ fn is_debug_type(format_type: FormatType) -> bool {
 }
 
 fn format_debug_fast(
-    output: &mut String,
+    output: &mut dyn Write,
     arg: &dyn FormatValue,
     format_type: FormatType,
 ) -> Result<(), Error> {
@@ -227,7 +304,7 @@ fn format_debug_fast(
 
 /// Let std::fmt handle width natively.
 fn format_full(
-    output: &mut String,
+    output: &mut dyn Write,
     arg: &dyn FormatValue,
     spec: &FormatSpec,
     width: Option<usize>,
@@ -237,7 +314,15 @@ fn format_full(
     let alt = spec.alternate;
 
     match spec.format_type {
-        FormatType::Display => {
+        // Every format trait is exposed as `Display` by the `Traited` adapter.
+        FormatType::Display
+        | FormatType::Octal
+        | FormatType::LowerHex
+        | FormatType::UpperHex
+        | FormatType::Binary
+        | FormatType::LowerExp
+        | FormatType::UpperExp
+        | FormatType::Pointer => {
             match (sign, alt, spec.zero_pad, width, precision) {
                 (false, false, _, None, None) => write!(output, "{}", arg),
                 (true, false, _, None, None) => write!(output, "{:+}", arg),
@@ -292,10 +377,18 @@ fn format_full(
                 (true, false, _, None, Some(p)) => write!(output, "{:+.prec$?}", dbg, prec = p),
                 (false, true, _, None, Some(p)) => write!(output, "{:#.prec$?}", dbg, prec = p),
                 (true, true, _, None, Some(p)) => write!(output, "{:+#.prec$?}", dbg, prec = p),
-                (false, false, false, Some(w), None) => write!(output, "{:width$?}", dbg, width = w),
-                (true, false, false, Some(w), None) => write!(output, "{:+width$?}", dbg, width = w),
-                (false, true, false, Some(w), None) => write!(output, "{:#width$?}", dbg, width = w),
-                (true, true, false, Some(w), None) => write!(output, "{:+#width$?}", dbg, width = w),
+                (false, false, false, Some(w), None) => {
+                    write!(output, "{:width$?}", dbg, width = w)
+                }
+                (true, false, false, Some(w), None) => {
+                    write!(output, "{:+width$?}", dbg, width = w)
+                }
+                (false, true, false, Some(w), None) => {
+                    write!(output, "{:#width$?}", dbg, width = w)
+                }
+                (true, true, false, Some(w), None) => {
+                    write!(output, "{:+#width$?}", dbg, width = w)
+                }
                 (false, false, false, Some(w), Some(p)) => {
                     write!(output, "{:width$.prec$?}", dbg, width = w, prec = p)
                 }
@@ -308,10 +401,18 @@ fn format_full(
                 (true, true, false, Some(w), Some(p)) => {
                     write!(output, "{:+#width$.prec$?}", dbg, width = w, prec = p)
                 }
-                (false, false, true, Some(w), None) => write!(output, "{:0width$?}", dbg, width = w),
-                (true, false, true, Some(w), None) => write!(output, "{:+0width$?}", dbg, width = w),
-                (false, true, true, Some(w), None) => write!(output, "{:#0width$?}", dbg, width = w),
-                (true, true, true, Some(w), None) => write!(output, "{:+#0width$?}", dbg, width = w),
+                (false, false, true, Some(w), None) => {
+                    write!(output, "{:0width$?}", dbg, width = w)
+                }
+                (true, false, true, Some(w), None) => {
+                    write!(output, "{:+0width$?}", dbg, width = w)
+                }
+                (false, true, true, Some(w), None) => {
+                    write!(output, "{:#0width$?}", dbg, width = w)
+                }
+                (true, true, true, Some(w), None) => {
+                    write!(output, "{:+#0width$?}", dbg, width = w)
+                }
                 (false, false, true, Some(w), Some(p)) => {
                     write!(output, "{:0width$.prec$?}", dbg, width = w, prec = p)
                 }
@@ -334,7 +435,6 @@ fn format_full(
             let dbg: &dyn Debug = arg;
             write!(output, "{:X?}", dbg)?;
         }
-        _ => unreachable!(),
     }
 
     Ok(())
@@ -351,7 +451,14 @@ fn format_core(
     let alt = spec.alternate;
 
     match spec.format_type {
-        FormatType::Display => {
+        FormatType::Display
+        | FormatType::Octal
+        | FormatType::LowerHex
+        | FormatType::UpperHex
+        | FormatType::Binary
+        | FormatType::LowerExp
+        | FormatType::UpperExp
+        | FormatType::Pointer => {
             match (sign, alt, precision) {
                 (false, false, None) => write!(buf, "{}", arg),
                 (true, false, None) => write!(buf, "{:+}", arg),
@@ -384,52 +491,42 @@ fn format_core(
             let dbg: &dyn Debug = arg;
             write!(buf, "{:X?}", dbg)?;
         }
-        _ => unreachable!(),
     }
 
     Ok(())
 }
 
 /// Manual padding.
-fn apply_padding(output: &mut String, raw: &str, spec: &FormatSpec, width: Option<usize>) {
+fn apply_padding(
+    output: &mut dyn Write,
+    raw: &str,
+    spec: &FormatSpec,
+    width: Option<usize>,
+) -> fmt::Result {
     let Some(width) = width else {
-        output.push_str(raw);
-        return;
+        return output.write_str(raw);
     };
 
     let char_count = raw.chars().count();
     if char_count >= width {
-        output.push_str(raw);
-        return;
+        return output.write_str(raw);
     }
 
     let pad_total = width - char_count;
     let fill = spec.fill.unwrap_or(' ');
     let align = spec.align.unwrap_or(Align::Left);
 
-    match align {
-        Align::Left => {
-            output.push_str(raw);
-            for _ in 0..pad_total {
-                output.push(fill);
-            }
-        }
-        Align::Right => {
-            for _ in 0..pad_total {
-                output.push(fill);
-            }
-            output.push_str(raw);
-        }
-        Align::Center => {
-            let left_pad = pad_total / 2;
-            let right_pad = pad_total - left_pad;
-            for _ in 0..left_pad {
-                output.push(fill);
-            }
-            output.push_str(raw);
-            for _ in 0..right_pad {
-                output.push(fill);
-            }
-        }
+    let (left_pad, right_pad) = match align {
+        Align::Left => (0, pad_total),
+        Align::Right => (pad_total, 0),
+        Align::Center => (pad_total / 2, pad_total - pad_total / 2),
+    };
+    for _ in 0..left_pad {
+        output.write_char(fill)?;
     }
+    output.write_str(raw)?;
+    for _ in 0..right_pad {
+        output.write_char(fill)?;
+    }
+    Ok(())
 }
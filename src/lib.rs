@@ -42,17 +42,69 @@
 //! assert_eq!(r2, "Bob has 7 items");
 //! ```
 
-mod ast;
+pub mod ast;
+mod builder;
+mod cache;
+mod canonical;
+mod diagnostic;
 mod error;
 mod format;
 mod macros;
+mod options;
 mod parser;
 mod renderer;
+#[cfg(feature = "serde")]
+mod serialize;
+mod signature;
+mod source;
+mod suggest;
 mod template;
 mod value;
 
-pub use ast::FormatType;
-pub use error::Error;
+pub use ast::{FormatType, Span};
+pub use builder::{Spec, TemplateBuilder};
+pub use cache::{CacheStats, TemplateCache};
+pub use diagnostic::Diagnostic;
+pub use error::{Error, ParseErrorKind};
+pub use options::{MissingPolicy, ParseOptions, RenderOptions};
 pub use renderer::Renderer;
-pub use template::Template;
-pub use value::FormatValue;
+#[cfg(feature = "serde")]
+pub use serialize::to_value;
+pub use signature::{Parameter, Signature, Usage, UsageKind};
+pub use source::{ArgSource, FormatArgs};
+pub use template::{Slot, Template};
+pub use value::{Arg, FormatValue, ToArg, Value};
+
+/// Items used by the macros and `#[derive(FormatArgs)]`; not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::value::{Autoref, ViaFormatValue, ViaToArg};
+}
+
+/// Derive [`ArgSource`] and [`FormatArgs`] for a struct with named fields.
+///
+/// ```
+/// use formatx::{FormatArgs, Template};
+///
+/// #[derive(FormatArgs)]
+/// struct Email {
+///     #[formatx(rename = "to")]
+///     recipient: String,
+///     subject: String,
+///     #[formatx(skip)]
+///     #[allow(dead_code)]
+///     body: String,
+/// }
+///
+/// let email = Email {
+///     recipient: "alice@example.com".into(),
+///     subject: "Hello".into(),
+///     body: String::new(),
+/// };
+/// let template = Template::new("To: {to}\nSubject: {subject}").unwrap();
+/// template.validate::<Email>().unwrap();
+/// let result = template.render().source(&email).finish().unwrap();
+/// assert_eq!(result, "To: alice@example.com\nSubject: Hello");
+/// ```
+#[cfg(feature = "derive")]
+pub use formatx_derive::FormatArgs;
@@ -5,6 +5,9 @@
 /// Returns [`Result`]`<`[`String`]`, `[`Error`](crate::Error)`>`. Produces [`Error::MissingArgument`](crate::Error::MissingArgument)
 /// if any placeholder references an argument that was not provided.
 ///
+/// The template is parsed through the [`TemplateCache`](crate::TemplateCache),
+/// so enabling the cache avoids re-parsing the same template on every call.
+///
 /// # Examples
 ///
 /// ```
@@ -22,15 +25,15 @@
 macro_rules! formatx {
     ($template:expr $(,)?) => {
         (|| -> ::std::result::Result<::std::string::String, $crate::Error> {
-            $crate::Template::new($template)?.render().finish()
+            $crate::Template::cached($template)?.render().finish()
         })()
     };
     ($template:expr, $($args:tt)*) => {
         (|| -> ::std::result::Result<::std::string::String, $crate::Error> {
-            let t = $crate::Template::new($template)?;
-            let mut r = t.render();
-            $crate::_formatx_internal!(r, $($args)*);
-            r.finish()
+            #[allow(unused_imports)]
+            use $crate::__private::{ViaFormatValue as _, ViaToArg as _};
+            let t = $crate::Template::cached($template)?;
+            $crate::_formatx_internal!(t.render(), $($args)*).finish()
         })()
     };
 }
@@ -40,6 +43,9 @@ macro_rules! formatx {
 /// Returns [`Result`]`<`[`String`]`, `[`Error`](crate::Error)`>`. Missing arguments are replaced with an
 /// empty string `""` instead of producing an error.
 ///
+/// Like [`formatx!`], the template is parsed through the
+/// [`TemplateCache`](crate::TemplateCache).
+///
 /// # Examples
 ///
 /// ```
@@ -53,37 +59,63 @@ macro_rules! formatx {
 macro_rules! formatxl {
     ($template:expr $(,)?) => {
         (|| -> ::std::result::Result<::std::string::String, $crate::Error> {
-            $crate::Template::new($template)?.render().finish_lenient()
+            $crate::Template::cached($template)?.render().finish_lenient()
         })()
     };
     ($template:expr, $($args:tt)*) => {
         (|| -> ::std::result::Result<::std::string::String, $crate::Error> {
-            let t = $crate::Template::new($template)?;
-            let mut r = t.render();
-            $crate::_formatx_internal!(r, $($args)*);
-            r.finish_lenient()
+            #[allow(unused_imports)]
+            use $crate::__private::{ViaFormatValue as _, ViaToArg as _};
+            let t = $crate::Template::cached($template)?;
+            $crate::_formatx_internal!(t.render(), $($args)*).finish_lenient()
         })()
     };
 }
 
 /// Internal helper macro for argument collection.
 ///
-/// Recursively processes `name = value` (named) and `value` (positional) arguments.
+/// Recursively processes `name = value` (named) and `value` (positional) arguments
+/// into a single method chain on the renderer, so temporaries in the arguments
+/// live until the output is rendered. Expects the `__private` dispatch traits
+/// to be in scope, see [`_formatx_arg!`].
 #[macro_export]
 #[doc(hidden)]
 macro_rules! _formatx_internal {
     ($r:expr, $name:ident = $value:expr $(,)?) => {
-        $r.named(stringify!($name), &$value);
+        $r.named_with(
+            stringify!($name),
+            (&&$crate::__private::Autoref(&$value)).__formatx_arg(),
+        )
     };
     ($r:expr, $value:expr $(,)?) => {
-        $r.arg(&$value);
+        $r.arg_with((&&$crate::__private::Autoref(&$value)).__formatx_arg())
     };
     ($r:expr, $name:ident = $value:expr, $($rest:tt)*) => {
-        $r.named(stringify!($name), &$value);
-        $crate::_formatx_internal!($r, $($rest)*);
+        $crate::_formatx_internal!(
+            $r.named_with(
+                stringify!($name),
+                (&&$crate::__private::Autoref(&$value)).__formatx_arg(),
+            ),
+            $($rest)*
+        )
     };
     ($r:expr, $value:expr, $($rest:tt)*) => {
-        $r.arg(&$value);
-        $crate::_formatx_internal!($r, $($rest)*);
+        $crate::_formatx_internal!(
+            $r.arg_with((&&$crate::__private::Autoref(&$value)).__formatx_arg()),
+            $($rest)*
+        )
     };
 }
+
+/// Internal helper macro converting a value to an [`Arg`](crate::Arg), with
+/// [`ToArg`](crate::ToArg) if its type implements it and
+/// [`Arg::new`](crate::Arg::new) otherwise.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _formatx_arg {
+    ($value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{ViaFormatValue as _, ViaToArg as _};
+        (&&$crate::__private::Autoref(&$value)).__formatx_arg()
+    }};
+}
@@ -0,0 +1,144 @@
+//! [`ParseOptions`] and [`RenderOptions`] - opt-in syntax extensions and
+//! missing/unused argument handling.
+
+use crate::ast::Span;
+use std::{fmt, sync::Arc};
+
+/// Syntax extensions for [`Template::with_options`](crate::Template::with_options).
+///
+/// All extensions are off by default, keeping parity with [`std::fmt`].
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{ParseOptions, Template};
+///
+/// let options = ParseOptions::new().with_defaults(true);
+/// let template = Template::with_options("Hi {name=anonymous:>10}!", options).unwrap();
+/// assert_eq!(template.render().finish().unwrap(), "Hi  anonymous!");
+/// assert_eq!(template.render().named("name", &"Bob").finish().unwrap(), "Hi        Bob!");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub(crate) defaults: bool,
+}
+
+impl ParseOptions {
+    /// Create the default options: no syntax extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow a default value after the argument, e.g. `{name=anonymous}` or
+    /// `{user.name=n/a:>10}`.
+    ///
+    /// The default is formatted as a string with the placeholder's format
+    /// spec when the argument is missing, taking precedence over the
+    /// [`MissingPolicy`]. It ends at the first unescaped `:` or `}`; write
+    /// `\\`, `\:`, `\{` and `\}` for the literal characters.
+    pub fn with_defaults(mut self, defaults: bool) -> Self {
+        self.defaults = defaults;
+        self
+    }
+}
+
+/// What to substitute for a placeholder whose argument is missing.
+///
+/// The policy applies to the placeholder's value argument as well as to its
+/// `$` width/precision parameters and `.*` precision argument. A substitute
+/// replaces the whole placeholder and is not padded.
+#[derive(Clone, Default)]
+pub enum MissingPolicy {
+    /// Return an error, as [`Renderer::finish`](crate::Renderer::finish) does
+    /// by default.
+    #[default]
+    Error,
+    /// Substitute an empty string, as
+    /// [`Renderer::finish_lenient`](crate::Renderer::finish_lenient) does.
+    Empty,
+    /// Keep the placeholder text verbatim, e.g. `{name:>10}`.
+    Keep,
+    /// Substitute a fixed marker.
+    Marker(String),
+    /// Substitute the string returned by a callback, called with the name of
+    /// the missing argument and the span of the placeholder.
+    Callback(Arc<MissingCallback>),
+}
+
+type MissingCallback = dyn Fn(&str, Span) -> String + Send + Sync;
+
+impl MissingPolicy {
+    /// Create a [`MissingPolicy::Callback`] from a closure.
+    pub fn callback(f: impl Fn(&str, Span) -> String + Send + Sync + 'static) -> Self {
+        Self::Callback(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for MissingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => f.write_str("Error"),
+            Self::Empty => f.write_str("Empty"),
+            Self::Keep => f.write_str("Keep"),
+            Self::Marker(marker) => f.debug_tuple("Marker").field(marker).finish(),
+            Self::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
+
+/// Options controlling how a [`Renderer`](crate::Renderer) produces output.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{MissingPolicy, RenderOptions, Template};
+///
+/// let template = Template::new("Hello {name:>8}, you have {n} messages").unwrap();
+/// let options = RenderOptions::new().with_missing(MissingPolicy::Keep);
+/// let result = template.render().named("n", &3).options(options).finish().unwrap();
+/// assert_eq!(result, "Hello {name:>8}, you have 3 messages");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    pub(crate) missing: MissingPolicy,
+    pub(crate) deny_unused: bool,
+}
+
+impl RenderOptions {
+    /// Create the default options: missing arguments are an error.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the policy for missing arguments.
+    pub fn with_missing(mut self, policy: MissingPolicy) -> Self {
+        self.missing = policy;
+        self
+    }
+
+    /// Return [`Error::UnusedArgument`](crate::Error::UnusedArgument) if an
+    /// argument added to the renderer is not used by any placeholder or
+    /// width/precision parameter. Off by default.
+    ///
+    /// Arguments looked up in an [`ArgSource`](crate::ArgSource) are not
+    /// checked.
+    ///
+    /// ```
+    /// use formatx::{Error, RenderOptions, Template};
+    ///
+    /// let template = Template::new("{} {name}").unwrap();
+    /// let err = template.render()
+    ///     .arg(&1)
+    ///     .arg(&2)
+    ///     .named("name", &"a")
+    ///     .named("extra", &"b")
+    ///     .options(RenderOptions::new().with_deny_unused(true))
+    ///     .finish()
+    ///     .unwrap_err();
+    /// assert_eq!(err.to_string(), "unused arguments: 1, `extra`");
+    /// ```
+    pub fn with_deny_unused(mut self, deny: bool) -> Self {
+        self.deny_unused = deny;
+        self
+    }
+}
@@ -1,10 +1,33 @@
 //! Single-pass parser for `std::fmt` - style format strings.
 
-use crate::{ast::*, error::Error};
+use crate::{
+    ast::*,
+    error::{Error, ParseErrorKind},
+    options::ParseOptions,
+};
 
 /// Parse a format string into a [`FormatString`] AST.
 pub fn parse(source: &str) -> Result<FormatString, Error> {
+    parse_with(source, &ParseOptions::new())
+}
+
+/// Parse a format string into a [`FormatString`] AST with syntax extensions.
+pub fn parse_with(source: &str, options: &ParseOptions) -> Result<FormatString, Error> {
+    let (parsed, errors) = parse_recovering(source, options);
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(parsed),
+    }
+}
+
+/// Parse a format string, recovering from errors.
+///
+/// A malformed placeholder is skipped up to its closing `}`, or up to the next
+/// `{` if that comes first, and an unmatched `}` is skipped on its own.
+/// Returns the segments that parsed and every error in source order.
+pub fn parse_recovering(source: &str, options: &ParseOptions) -> (FormatString, Vec<Error>) {
     let mut segments = Vec::new();
+    let mut errors = Vec::new();
     let bytes = source.as_bytes();
     let len = bytes.len();
     let mut pos = 0;
@@ -15,31 +38,48 @@ pub fn parse(source: &str) -> Result<FormatString, Error> {
             b'{' => {
                 if pos + 1 < len && bytes[pos + 1] == b'{' {
                     // Escaped `{{`
-                    segments.push(Segment::EscapedOpen);
+                    segments.push(Segment::EscapedOpen(Span {
+                        start: pos,
+                        end: pos + 2,
+                    }));
                     pos += 2;
                 } else {
                     // Start of placeholder `{...}`
                     let start = pos;
                     pos += 1; // skip `{`
-                    let (placeholder, end) =
-                        parse_placeholder(source, pos, start, &mut implicit_counter)?;
-                    segments.push(Segment::Placeholder(placeholder));
-                    pos = end;
+                    match parse_placeholder(source, pos, start, &mut implicit_counter, options) {
+                        Ok((placeholder, end)) => {
+                            segments.push(Segment::Placeholder(placeholder));
+                            pos = end;
+                        }
+                        Err(error) => {
+                            errors.push(error);
+                            pos = match bytes[pos..].iter().position(|&b| b == b'{' || b == b'}') {
+                                Some(i) if bytes[pos + i] == b'}' => pos + i + 1,
+                                Some(i) => pos + i,
+                                None => len,
+                            };
+                        }
+                    }
                 }
             }
             b'}' => {
                 if pos + 1 < len && bytes[pos + 1] == b'}' {
                     // Escaped `}}`
-                    segments.push(Segment::EscapedClose);
+                    segments.push(Segment::EscapedClose(Span {
+                        start: pos,
+                        end: pos + 2,
+                    }));
                     pos += 2;
                 } else {
-                    return Err(Error::Parse {
+                    errors.push(Error::Parse {
                         span: Span {
                             start: pos,
                             end: pos + 1,
                         },
-                        message: "unmatched `}`".to_string(),
+                        kind: ParseErrorKind::UnmatchedClose,
                     });
+                    pos += 1;
                 }
             }
             _ => {
@@ -53,7 +93,43 @@ pub fn parse(source: &str) -> Result<FormatString, Error> {
         }
     }
 
-    Ok(FormatString { segments })
+    let names = intern_names(source, &mut segments);
+    (FormatString::new(segments, names), errors)
+}
+
+/// Assign each distinct argument name a slot, shared by all of its uses.
+/// Returns the span of the first use of each name, indexed by slot.
+pub(crate) fn intern_names(source: &str, segments: &mut [Segment]) -> Vec<Span> {
+    let mut names: Vec<Span> = Vec::new();
+    let mut intern = |name: &mut Name| {
+        let text = name.span.slice(source);
+        name.slot = match names.iter().position(|span| span.slice(source) == text) {
+            Some(slot) => slot,
+            None => {
+                names.push(name.span);
+                names.len() - 1
+            }
+        };
+    };
+
+    for segment in segments {
+        let Segment::Placeholder(p) = segment else {
+            continue;
+        };
+        if let Argument::Named(name) = &mut p.argument {
+            intern(name);
+        }
+        let precision = match &mut p.spec.precision {
+            Some(Precision::Count(count)) => Some(count),
+            _ => None,
+        };
+        for count in [p.spec.width.as_mut(), precision].into_iter().flatten() {
+            if let Count::Param(CountParam::Named(name)) = count {
+                intern(name);
+            }
+        }
+    }
+    names
 }
 
 /// Parse the inside of a `{...}` placeholder, starting right after the `{`.
@@ -63,12 +139,26 @@ fn parse_placeholder(
     mut pos: usize,
     brace_start: usize,
     implicit_counter: &mut usize,
+    options: &ParseOptions,
 ) -> Result<(Placeholder, usize), Error> {
     let bytes = source.as_bytes();
     let len = bytes.len();
 
-    // Parse argument (before `:` or `}`)
-    let argument = parse_argument(source, &mut pos, implicit_counter)?;
+    // Parse argument and path (before `:` or `}`)
+    let argument = parse_argument(source, &mut pos, implicit_counter, options)?;
+    let path = if matches!(argument, Argument::Implicit) {
+        Vec::new()
+    } else {
+        parse_path(source, &mut pos, options)?
+    };
+
+    // Parse default value (after `=`)
+    let default = if options.defaults && pos < len && bytes[pos] == b'=' {
+        pos += 1; // skip `=`
+        Some(parse_default(source, &mut pos)?)
+    } else {
+        None
+    };
 
     // Parse format spec (after `:`)
     let spec = if pos < len && bytes[pos] == b':' {
@@ -85,13 +175,15 @@ fn parse_placeholder(
                 start: brace_start,
                 end: pos.min(len),
             },
-            message: "unmatched `{`".to_string(),
+            kind: ParseErrorKind::UnmatchedOpen,
         });
     }
     pos += 1; // skip `}`
 
     let placeholder = Placeholder {
         argument,
+        path,
+        default,
         spec,
         span: Span {
             start: brace_start,
@@ -107,6 +199,7 @@ fn parse_argument(
     source: &str,
     pos: &mut usize,
     implicit_counter: &mut usize,
+    options: &ParseOptions,
 ) -> Result<Argument, Error> {
     let bytes = source.as_bytes();
     let len = bytes.len();
@@ -117,7 +210,7 @@ fn parse_argument(
                 start: *pos,
                 end: *pos,
             },
-            message: "unexpected end of format string".to_string(),
+            kind: ParseErrorKind::UnexpectedEnd,
         });
     }
 
@@ -133,12 +226,12 @@ fn parse_argument(
         while *pos < len && bytes[*pos].is_ascii_digit() {
             *pos += 1;
         }
-        // Must be followed by `}` or `:` -not `$` (that's a count param, not here)
-        if *pos < len && (bytes[*pos] == b'}' || bytes[*pos] == b':') {
+        // Must be followed by `}`, `:` or a path -not `$` (that's a count param, not here)
+        if *pos < len && is_argument_end(bytes[*pos], options) {
             let num_str = &source[start..*pos];
             let index = num_str.parse::<usize>().map_err(|_| Error::Parse {
                 span: Span { start, end: *pos },
-                message: format!("invalid positional argument: `{num_str}`"),
+                kind: ParseErrorKind::InvalidNumber(num_str.to_string()),
             })?;
             return Ok(Argument::Positional(index));
         }
@@ -152,22 +245,170 @@ fn parse_argument(
         while *pos < len && (bytes[*pos].is_ascii_alphanumeric() || bytes[*pos] == b'_') {
             *pos += 1;
         }
-        if *pos < len && (bytes[*pos] == b'}' || bytes[*pos] == b':') {
-            return Ok(Argument::Named(Span {
-                start: name_start,
-                end: *pos,
+        if *pos < len && is_argument_end(bytes[*pos], options) {
+            return Ok(Argument::Named(Name {
+                span: Span {
+                    start: name_start,
+                    end: *pos,
+                },
+                slot: 0,
             }));
         }
         // Reset if not valid
         *pos = start;
     }
 
+    // Point at the first character that can't continue the argument, or at
+    // the start of an argument that is only invalid as a whole, like `0a`.
+    let mut end = start;
+    while end < len && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+        end += 1;
+    }
+    if end >= len {
+        return Err(Error::Parse {
+            span: Span {
+                start: len,
+                end: len,
+            },
+            kind: ParseErrorKind::UnexpectedEnd,
+        });
+    }
+    if end > start && is_argument_end(bytes[end], options) {
+        end = start;
+    }
+    Err(unexpected_char(
+        source,
+        end,
+        ParseErrorKind::InvalidArgument,
+    ))
+}
+
+/// Parse field and index accesses after the argument, e.g. `.name` and `[0]`.
+fn parse_path(
+    source: &str,
+    pos: &mut usize,
+    options: &ParseOptions,
+) -> Result<Vec<PathSegment>, Error> {
+    let bytes = source.as_bytes();
+    let len = bytes.len();
+    let mut path = Vec::new();
+
+    while *pos < len {
+        let start = *pos;
+        match bytes[*pos] {
+            b'.' => {
+                *pos += 1;
+                if *pos >= len || !(bytes[*pos].is_ascii_alphabetic() || bytes[*pos] == b'_') {
+                    return Err(Error::Parse {
+                        span: Span {
+                            start,
+                            end: (*pos + 1).min(len),
+                        },
+                        kind: ParseErrorKind::ExpectedField,
+                    });
+                }
+                let name_start = *pos;
+                while *pos < len && (bytes[*pos].is_ascii_alphanumeric() || bytes[*pos] == b'_') {
+                    *pos += 1;
+                }
+                path.push(PathSegment::Field(Span {
+                    start: name_start,
+                    end: *pos,
+                }));
+            }
+            b'[' => {
+                *pos += 1;
+                let num_start = *pos;
+                while *pos < len && bytes[*pos].is_ascii_digit() {
+                    *pos += 1;
+                }
+                if *pos == num_start || *pos >= len || bytes[*pos] != b']' {
+                    return Err(Error::Parse {
+                        span: Span {
+                            start,
+                            end: (*pos + 1).min(len),
+                        },
+                        kind: ParseErrorKind::ExpectedIndex,
+                    });
+                }
+                let num_str = &source[num_start..*pos];
+                let index = num_str.parse::<usize>().map_err(|_| Error::Parse {
+                    span: Span {
+                        start: num_start,
+                        end: *pos,
+                    },
+                    kind: ParseErrorKind::InvalidNumber(num_str.to_string()),
+                })?;
+                *pos += 1; // skip `]`
+                path.push(PathSegment::Index(index));
+            }
+            b'}' | b':' => break,
+            b'=' if options.defaults => break,
+            _ => {
+                return Err(unexpected_char(
+                    source,
+                    start,
+                    ParseErrorKind::InvalidArgument,
+                ));
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+/// Check if a byte can follow a placeholder argument.
+fn is_argument_end(b: u8, options: &ParseOptions) -> bool {
+    matches!(b, b'}' | b':' | b'.' | b'[') || (options.defaults && b == b'=')
+}
+
+/// Parse a default value after `=`, up to the unescaped `:` or `}`.
+///
+/// `\\`, `\:`, `\{` and `\}` escape `\`, `:`, `{` and `}`.
+fn parse_default(source: &str, pos: &mut usize) -> Result<String, Error> {
+    let start = *pos;
+    let mut value = String::new();
+    let mut chars = source[start..].char_indices();
+
+    while let Some((offset, c)) = chars.next() {
+        let at = start + offset;
+        match c {
+            ':' | '}' => {
+                *pos = at;
+                return Ok(value);
+            }
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('\\' | ':' | '{' | '}'))) => value.push(escaped),
+                Some((next, escaped)) => {
+                    return Err(Error::Parse {
+                        span: Span {
+                            start: at,
+                            end: start + next + escaped.len_utf8(),
+                        },
+                        kind: ParseErrorKind::InvalidEscape(escaped),
+                    });
+                }
+                None => break,
+            },
+            '{' => {
+                return Err(Error::Parse {
+                    span: Span {
+                        start: at,
+                        end: at + 1,
+                    },
+                    kind: ParseErrorKind::UnescapedBrace,
+                });
+            }
+            _ => value.push(c),
+        }
+    }
+
     Err(Error::Parse {
         span: Span {
             start,
-            end: *pos + 1,
+            end: source.len(),
         },
-        message: "invalid placeholder argument".to_string(),
+        kind: ParseErrorKind::UnterminatedDefault,
     })
 }
 
@@ -219,8 +460,8 @@ fn parse_format_spec(
         return Ok(spec);
     }
 
-    // Zero-pad `0` - only if followed by a digit or `}` or `.` or type char
-    // (a bare `0` before width is zero-padding, not width)
+    // Zero-pad `0` - only if followed by a digit, `}`, `.`, type char or a
+    // named width param (a bare `0` before width is zero-padding, not width)
     if bytes[*pos] == b'0' {
         // Peek ahead: if next char is a digit, this is zero-pad prefix
         // If next char is `}` or `.` or a type char, this could be width=0 or zero-pad
@@ -230,7 +471,13 @@ fn parse_format_spec(
         } else {
             b'}'
         };
-        if next.is_ascii_digit() || next == b'}' || next == b'.' || is_type_char(next) {
+        if next.is_ascii_digit()
+            || next == b'}'
+            || next == b'.'
+            || is_type_char(next)
+            || next.is_ascii_alphabetic()
+            || next == b'_'
+        {
             spec.zero_pad = true;
             *pos += 1;
         }
@@ -241,7 +488,11 @@ fn parse_format_spec(
     }
 
     // Width
+    let start = *pos;
     spec.width = parse_count(source, pos)?;
+    if spec.width.is_some() {
+        spec.width_span = Some(Span { start, end: *pos });
+    }
 
     if *pos >= len || bytes[*pos] == b'}' {
         return Ok(spec);
@@ -249,6 +500,7 @@ fn parse_format_spec(
 
     // Precision (`.`)
     if bytes[*pos] == b'.' {
+        let start = *pos;
         *pos += 1; // skip `.`
 
         if *pos >= len {
@@ -257,7 +509,7 @@ fn parse_format_spec(
                     start: *pos - 1,
                     end: *pos,
                 },
-                message: "expected precision after `.`".to_string(),
+                kind: ParseErrorKind::ExpectedPrecision,
             });
         }
 
@@ -277,10 +529,11 @@ fn parse_format_spec(
                         start: *pos - 1,
                         end: *pos,
                     },
-                    message: "expected precision value after `.`".to_string(),
+                    kind: ParseErrorKind::ExpectedPrecision,
                 });
             }
         }
+        spec.precision_span = Some(Span { start, end: *pos });
     }
 
     if *pos >= len || bytes[*pos] == b'}' {
@@ -356,7 +609,7 @@ fn parse_count(source: &str, pos: &mut usize) -> Result<Option<Count>, Error> {
             let num_str = &source[start..*pos];
             let index = num_str.parse::<usize>().map_err(|_| Error::Parse {
                 span: Span { start, end: *pos },
-                message: format!("invalid count parameter: `{num_str}`"),
+                kind: ParseErrorKind::InvalidNumber(num_str.to_string()),
             })?;
             *pos += 1; // skip `$`
             return Ok(Some(Count::Param(CountParam::Positional(index))));
@@ -365,7 +618,7 @@ fn parse_count(source: &str, pos: &mut usize) -> Result<Option<Count>, Error> {
         let num_str = &source[start..*pos];
         let value = num_str.parse::<usize>().map_err(|_| Error::Parse {
             span: Span { start, end: *pos },
-            message: format!("invalid count: `{num_str}`"),
+            kind: ParseErrorKind::InvalidNumber(num_str.to_string()),
         })?;
         return Ok(Some(Count::Literal(value)));
     }
@@ -378,9 +631,12 @@ fn parse_count(source: &str, pos: &mut usize) -> Result<Option<Count>, Error> {
         }
         if *pos < len && bytes[*pos] == b'$' {
             *pos += 1; // skip `$`
-            return Ok(Some(Count::Param(CountParam::Named(Span {
-                start: name_start,
-                end: *pos - 1, // exclude `$`
+            return Ok(Some(Count::Param(CountParam::Named(Name {
+                span: Span {
+                    start: name_start,
+                    end: *pos - 1, // exclude `$`
+                },
+                slot: 0,
             }))));
         }
         // Not a count param -reset
@@ -443,20 +699,30 @@ fn parse_format_type(source: &str, pos: &mut usize) -> Result<FormatType, Error>
                 FormatType::UpperHex
             }
         }
-        c => {
-            return Err(Error::Parse {
-                span: Span {
-                    start,
-                    end: *pos + 1,
-                },
-                message: format!("unknown format type: `{}`", c as char),
-            });
+        _ => {
+            return Err(unexpected_char(
+                source,
+                start,
+                ParseErrorKind::UnknownFormatType,
+            ));
         }
     };
 
     Ok(ty)
 }
 
+/// An error for the unexpected character at `pos`.
+fn unexpected_char(source: &str, pos: usize, kind: fn(char) -> ParseErrorKind) -> Error {
+    let found = source[pos..].chars().next().unwrap_or_default();
+    Error::Parse {
+        span: Span {
+            start: pos,
+            end: pos + found.len_utf8(),
+        },
+        kind: kind(found),
+    }
+}
+
 /// Check if a byte is a format type character.
 fn is_type_char(b: u8) -> bool {
     matches!(b, b'?' | b'o' | b'x' | b'X' | b'b' | b'e' | b'E' | b'p')
@@ -466,10 +732,6 @@ fn is_type_char(b: u8) -> bool {
 mod tests {
     use super::*;
 
-    fn resolve(source: &str, span: Span) -> &str {
-        &source[span.start..span.end]
-    }
-
     #[test]
     fn empty_string() {
         let result = parse("").unwrap();
@@ -482,7 +744,7 @@ mod tests {
         let result = parse(source).unwrap();
         assert_eq!(result.segments.len(), 1);
         if let Segment::Literal(span) = &result.segments[0] {
-            assert_eq!(resolve(source, *span), "hello world");
+            assert_eq!(span.slice(source), "hello world");
         } else {
             panic!("expected Literal");
         }
@@ -492,8 +754,14 @@ mod tests {
     fn escaped_braces() {
         let result = parse("{{}}").unwrap();
         assert_eq!(result.segments.len(), 2);
-        assert!(matches!(result.segments[0], Segment::EscapedOpen));
-        assert!(matches!(result.segments[1], Segment::EscapedClose));
+        assert!(matches!(
+            result.segments[0],
+            Segment::EscapedOpen(Span { start: 0, end: 2 })
+        ));
+        assert!(matches!(
+            result.segments[1],
+            Segment::EscapedClose(Span { start: 2, end: 4 })
+        ));
     }
 
     #[test]
@@ -521,14 +789,73 @@ mod tests {
         let source = "{name}";
         let result = parse(source).unwrap();
         if let Segment::Placeholder(p) = &result.segments[0] {
-            if let Argument::Named(span) = &p.argument {
-                assert_eq!(resolve(source, *span), "name");
+            if let Argument::Named(name) = &p.argument {
+                assert_eq!(name.span.slice(source), "name");
             } else {
                 panic!("expected Named");
             }
         }
     }
 
+    #[test]
+    fn nested_path() {
+        let source = "{order.lines[2].sku} {0[1]}";
+        let result = parse(source).unwrap();
+        if let Segment::Placeholder(p) = &result.segments[0] {
+            assert!(
+                matches!(p.argument, Argument::Named(name) if name.span.slice(source) == "order")
+            );
+            assert_eq!(p.path.len(), 3);
+            assert!(matches!(p.path[0], PathSegment::Field(span) if span.slice(source) == "lines"));
+            assert!(matches!(p.path[1], PathSegment::Index(2)));
+            assert!(matches!(p.path[2], PathSegment::Field(span) if span.slice(source) == "sku"));
+        } else {
+            panic!("expected Placeholder");
+        }
+        if let Segment::Placeholder(p) = &result.segments[2] {
+            assert!(matches!(p.argument, Argument::Positional(0)));
+            assert!(matches!(p.path[..], [PathSegment::Index(1)]));
+        } else {
+            panic!("expected Placeholder");
+        }
+    }
+
+    #[test]
+    fn invalid_path() {
+        assert!(parse("{a.}").is_err());
+        assert!(parse("{a.1}").is_err());
+        assert!(parse("{a[]}").is_err());
+        assert!(parse("{a[x]}").is_err());
+        assert!(parse("{a[0}").is_err());
+        assert!(parse("{a.b c}").is_err());
+        assert!(parse("{.a}").is_err());
+    }
+
+    #[test]
+    fn default_values() {
+        let options = ParseOptions::new().with_defaults(true);
+        let source = r"{name=anon:>8} {0.x=} {a=\:\{\}\\ é}";
+        let result = parse_with(source, &options).unwrap();
+        let defaults: Vec<_> = result
+            .segments
+            .iter()
+            .filter_map(|seg| match seg {
+                Segment::Placeholder(p) => Some(p.default.as_deref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(defaults, [Some("anon"), Some(""), Some(r":{}\ é")]);
+        if let Segment::Placeholder(p) = &result.segments[0] {
+            assert!(matches!(p.spec.width, Some(Count::Literal(8))));
+        }
+
+        assert!(parse("{name=anon}").is_err());
+        assert!(parse_with("{=anon}", &options).is_err());
+        assert!(parse_with("{a=x{y}", &options).is_err());
+        assert!(parse_with(r"{a=\n}", &options).is_err());
+        assert!(parse_with("{a=abc", &options).is_err());
+    }
+
     #[test]
     fn format_spec_width_precision() {
         let result = parse("{:10.5}").unwrap();
@@ -538,6 +865,20 @@ mod tests {
                 p.spec.precision,
                 Some(Precision::Count(Count::Literal(5)))
             ));
+            assert!(matches!(p.spec.width_span, Some(Span { start: 2, end: 4 })));
+            assert!(matches!(
+                p.spec.precision_span,
+                Some(Span { start: 4, end: 6 })
+            ));
+        }
+
+        let result = parse("{:>w$.*}").unwrap();
+        if let Segment::Placeholder(p) = &result.segments[0] {
+            assert!(matches!(p.spec.width_span, Some(Span { start: 3, end: 5 })));
+            assert!(matches!(
+                p.spec.precision_span,
+                Some(Span { start: 5, end: 7 })
+            ));
         }
     }
 
@@ -587,19 +928,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn interned_names() {
+        let source = "{b} {a:b$} {0:.a$} {b}";
+        let result = parse(source).unwrap();
+        let names: Vec<_> = result.names.iter().map(|span| span.slice(source)).collect();
+        assert_eq!(names, ["b", "a"]);
+        if let Segment::Placeholder(p) = &result.segments[2] {
+            assert!(matches!(p.argument, Argument::Named(name) if name.slot == 1));
+            assert!(matches!(
+                p.spec.width,
+                Some(Count::Param(CountParam::Named(name))) if name.slot == 0
+            ));
+        }
+        if let Segment::Placeholder(p) = &result.segments[6] {
+            assert!(matches!(p.argument, Argument::Named(name) if name.slot == 0));
+        }
+    }
+
     #[test]
     fn param_width_and_precision() {
         let source = "{:width$.prec$}";
         let result = parse(source).unwrap();
         if let Segment::Placeholder(p) = &result.segments[0] {
-            if let Some(Count::Param(CountParam::Named(span))) = &p.spec.width {
-                assert_eq!(resolve(source, *span), "width");
+            if let Some(Count::Param(CountParam::Named(name))) = &p.spec.width {
+                assert_eq!(name.span.slice(source), "width");
             } else {
                 panic!("expected named width param");
             }
-            if let Some(Precision::Count(Count::Param(CountParam::Named(span)))) = &p.spec.precision
+            if let Some(Precision::Count(Count::Param(CountParam::Named(name)))) = &p.spec.precision
             {
-                assert_eq!(resolve(source, *span), "prec");
+                assert_eq!(name.span.slice(source), "prec");
             } else {
                 panic!("expected named precision param");
             }
@@ -616,6 +975,46 @@ mod tests {
         assert!(parse("}").is_err());
     }
 
+    #[test]
+    fn error_kinds() {
+        let kind = |source| match parse(source) {
+            Err(Error::Parse { kind, .. }) => kind,
+            other => panic!("expected parse error for {source:?}, got {other:?}"),
+        };
+        assert_eq!(kind("{"), ParseErrorKind::UnexpectedEnd);
+        assert_eq!(kind("{0:>5"), ParseErrorKind::UnmatchedOpen);
+        assert_eq!(kind("a}"), ParseErrorKind::UnmatchedClose);
+        assert_eq!(kind("{a-b}"), ParseErrorKind::InvalidArgument('-'));
+        assert_eq!(kind("{é}"), ParseErrorKind::InvalidArgument('é'));
+        assert_eq!(kind("{0a}"), ParseErrorKind::InvalidArgument('0'));
+        assert_eq!(kind("{abc"), ParseErrorKind::UnexpectedEnd);
+        assert_eq!(kind("{a.}"), ParseErrorKind::ExpectedField);
+        assert_eq!(kind("{a[x]}"), ParseErrorKind::ExpectedIndex);
+        assert_eq!(kind("{:.}"), ParseErrorKind::ExpectedPrecision);
+        assert_eq!(
+            kind("{99999999999999999999999}"),
+            ParseErrorKind::InvalidNumber("99999999999999999999999".to_string())
+        );
+        assert_eq!(kind("{:q}"), ParseErrorKind::UnknownFormatType('q'));
+
+        let defaults = ParseOptions::new().with_defaults(true);
+        let kind = |source| match parse_with(source, &defaults) {
+            Err(Error::Parse { kind, .. }) => kind,
+            other => panic!("expected parse error for {source:?}, got {other:?}"),
+        };
+        assert_eq!(kind(r"{a=\n}"), ParseErrorKind::InvalidEscape('n'));
+        assert_eq!(kind("{a={}"), ParseErrorKind::UnescapedBrace);
+        assert_eq!(kind("{a=x"), ParseErrorKind::UnterminatedDefault);
+
+        assert_eq!(
+            parse("{é}").unwrap_err(),
+            Error::Parse {
+                span: Span { start: 1, end: 3 },
+                kind: ParseErrorKind::InvalidArgument('é'),
+            }
+        );
+    }
+
     #[test]
     fn debug_hex_types() {
         let result = parse("{:x?}").unwrap();
@@ -1,7 +1,19 @@
 //! The [`Renderer`] builder - collects arguments and produces formatted output.
 
-use crate::{error::Error, format, template::Template, value::FormatValue};
-use std::fmt::{Debug, Display};
+use crate::{
+    error::Error,
+    format,
+    options::{MissingPolicy, RenderOptions},
+    source::ArgSource,
+    template::{Slot, Template},
+    value::{Arg, Stored, ToArg, Value},
+};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    fmt::{self, Display},
+    io,
+};
 
 /// A builder for rendering a [`Template`] with arguments.
 ///
@@ -19,10 +31,35 @@ use std::fmt::{Debug, Display};
 ///     .unwrap();
 /// assert_eq!(result, "1 + 2 = 3");
 /// ```
+///
+/// A renderer also implements [`Display`], formatting lazily into the outer
+/// formatter according to its [`RenderOptions`]:
+///
+/// ```
+/// use formatx::Template;
+///
+/// let template = Template::new("{name}!").unwrap();
+/// let line = format!("[{:>8}]", template.render().named("name", &"Alice"));
+/// assert_eq!(line, "[  Alice!]");
+/// ```
+///
+/// # Panics
+///
+/// `format!`, [`ToString::to_string`] and `println!` panic if rendering fails,
+/// e.g. on a missing argument, because they treat a formatting error as a
+/// bug. When a render can fail, use `write!` and recover the error with
+/// [`take_error`](Self::take_error), or call [`finish`](Self::finish).
 pub struct Renderer<'a> {
     template: &'a Template,
-    args: Vec<&'a dyn FormatValue>,
-    named: Vec<(&'a str, usize)>,
+    args: Vec<Stored<'a>>,
+    named: Vec<(Cow<'a, str>, usize)>,
+    sources: Vec<&'a dyn ArgSource>,
+    slots: Vec<Option<usize>>,
+    options: RenderOptions,
+    used: Vec<Cell<bool>>,
+    error: Cell<Option<Error>>,
+    /// Set when a [`Slot`] from another template was passed.
+    slot_error: Option<Error>,
 }
 
 impl<'a> Renderer<'a> {
@@ -32,31 +69,166 @@ impl<'a> Renderer<'a> {
             template,
             args: Vec::new(),
             named: Vec::new(),
+            sources: Vec::new(),
+            slots: Vec::new(),
+            options: RenderOptions::new(),
+            used: Vec::new(),
+            error: Cell::new(None),
+            slot_error: None,
         }
     }
 
-    /// Add a positional argument.
+    /// Add a positional argument with the traits given by its [`ToArg`] impl.
+    ///
+    /// Numbers also support the radix and exponent traits; use
+    /// [`arg_with`](Self::arg_with) and [`Arg::new`] for other `Display + Debug`
+    /// types.
     #[inline]
-    pub fn arg(&mut self, value: &'a (impl Display + Debug)) -> &mut Self {
-        self.args.push(value as &dyn FormatValue);
+    pub fn arg(&mut self, value: &'a (impl ToArg + ?Sized)) -> &mut Self {
+        self.push(Stored::Borrowed(value.to_arg()));
         self
     }
 
-    /// Add a named argument.
+    /// Add a named argument with the traits given by its [`ToArg`] impl.
     #[inline]
-    pub fn named(&mut self, name: &'a str, value: &'a (impl Display + Debug)) -> &mut Self {
-        let index = self.args.len();
-        self.args.push(value as &dyn FormatValue);
-        self.named.push((name, index));
+    pub fn named(&mut self, name: &'a str, value: &'a (impl ToArg + ?Sized)) -> &mut Self {
+        self.push_named(Cow::Borrowed(name), Stored::Borrowed(value.to_arg()))
+    }
+
+    /// Add a positional argument with an explicit set of supported traits.
+    #[inline]
+    pub fn arg_with(&mut self, arg: Arg<'a>) -> &mut Self {
+        self.push(Stored::Borrowed(arg));
+        self
+    }
+
+    /// Add a named argument with an explicit set of supported traits.
+    #[inline]
+    pub fn named_with(&mut self, name: &'a str, arg: Arg<'a>) -> &mut Self {
+        self.push_named(Cow::Borrowed(name), Stored::Borrowed(arg))
+    }
+
+    /// Add an owned positional argument.
+    #[inline]
+    pub fn value(&mut self, value: impl Into<Value>) -> &mut Self {
+        self.push(Stored::Owned(value.into()));
+        self
+    }
+
+    /// Add an owned named argument.
+    ///
+    /// Neither the name nor the value needs to outlive the renderer, which is
+    /// convenient when arguments are built at runtime.
+    #[inline]
+    pub fn named_value(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: impl Into<Value>,
+    ) -> &mut Self {
+        self.push_named(name.into(), Stored::Owned(value.into()))
+    }
+
+    /// Add a positional argument formatted with `{:p}` as the address of `value`.
+    ///
+    /// ```
+    /// use formatx::Template;
+    ///
+    /// let value = 42;
+    /// let template = Template::new("{:p}").unwrap();
+    /// let result = template.render().ptr(&value).finish().unwrap();
+    /// assert_eq!(result, format!("{:p}", &value));
+    /// ```
+    #[inline]
+    pub fn ptr<T>(&mut self, value: &'a T) -> &mut Self {
+        self.push(Stored::Borrowed(Arg::pointer(value)));
+        self
+    }
+
+    /// Add a named argument formatted with `{:p}` as the address of `value`.
+    #[inline]
+    pub fn named_ptr<T>(&mut self, name: &'a str, value: &'a T) -> &mut Self {
+        self.push_named(Cow::Borrowed(name), Stored::Borrowed(Arg::pointer(value)))
+    }
+
+    /// Set a named argument by its [`Slot`].
+    ///
+    /// Unlike [`named`](Self::named), placeholders using this argument are
+    /// resolved without comparing names.
+    ///
+    /// ```
+    /// use formatx::Template;
+    ///
+    /// let template = Template::new("{user} has {n} items").unwrap();
+    /// let (user, n) = (template.slot("user").unwrap(), template.slot("n").unwrap());
+    /// for i in 0..3 {
+    ///     let result = template.render().slot(user, &"Alice").slot(n, &i).finish().unwrap();
+    ///     assert_eq!(result, format!("Alice has {i} items"));
+    /// }
+    /// ```
+    ///
+    /// Rendering returns [`Error::ForeignSlot`] if `slot` was not obtained
+    /// from this renderer's template.
+    #[inline]
+    pub fn slot(&mut self, slot: Slot, value: &'a (impl ToArg + ?Sized)) -> &mut Self {
+        self.set_slot(slot, Stored::Borrowed(value.to_arg()))
+    }
+
+    /// Set a named argument by its [`Slot`] with an explicit set of supported traits.
+    ///
+    /// Rendering returns [`Error::ForeignSlot`] if `slot` was not obtained
+    /// from this renderer's template.
+    #[inline]
+    pub fn slot_with(&mut self, slot: Slot, arg: Arg<'a>) -> &mut Self {
+        self.set_slot(slot, Stored::Borrowed(arg))
+    }
+
+    /// Set an owned named argument by its [`Slot`].
+    ///
+    /// Rendering returns [`Error::ForeignSlot`] if `slot` was not obtained
+    /// from this renderer's template.
+    #[inline]
+    pub fn slot_value(&mut self, slot: Slot, value: impl Into<Value>) -> &mut Self {
+        self.set_slot(slot, Stored::Owned(value.into()))
+    }
+
+    /// Add an [`ArgSource`] to look up arguments in.
+    ///
+    /// Arguments added directly to the renderer take precedence. Lookups that
+    /// miss them fall back to each source in the order they were added, using
+    /// the same index or name. As with `format!`, named arguments also take
+    /// up a positional index, so a source's values are only reached at indices
+    /// past the supplied arguments.
+    ///
+    /// ```
+    /// use formatx::Template;
+    ///
+    /// let values = vec!["a", "b"];
+    /// let template = Template::new("{0} {1} {x}").unwrap();
+    /// let result = template.render().named("x", &1).source(&values).finish().unwrap();
+    /// assert_eq!(result, "1 b 1");
+    /// ```
+    #[inline]
+    pub fn source(&mut self, source: &'a dyn ArgSource) -> &mut Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Set the [`RenderOptions`] used by [`finish`](Self::finish),
+    /// [`write_to`](Self::write_to), [`write_io`](Self::write_io) and
+    /// [`Display`].
+    #[inline]
+    pub fn options(&mut self, options: RenderOptions) -> &mut Self {
+        self.options = options;
         self
     }
 
     /// **Strict**: produce the formatted output.
     ///
     /// Returns `Err(Error::MissingArgument)` if any placeholder references an
-    /// argument that was not provided.
+    /// argument that was not provided, unless another [`MissingPolicy`] was
+    /// set with [`options`](Self::options).
     pub fn finish(&self) -> Result<String, Error> {
-        self.render_inner(true)
+        self.render_inner(&self.options)
     }
 
     /// **Lenient**: produce the formatted output.
@@ -64,20 +236,262 @@ impl<'a> Renderer<'a> {
     /// Missing arguments are replaced with an empty string `""` instead of
     /// producing an error.
     pub fn finish_lenient(&self) -> Result<String, Error> {
-        self.render_inner(false)
+        self.render_inner(&self.lenient())
     }
 
-    fn render_inner(&self, strict: bool) -> Result<String, Error> {
-        let source = self.template.source();
-        let mut output = String::with_capacity(source.len());
-        format::render(
-            &mut output,
-            source,
-            self.template.parsed(),
-            &self.args,
-            &self.named,
-            strict,
-        )?;
+    /// **Strict**: write the formatted output into `output`.
+    ///
+    /// Segments are written to `output` as they are rendered, without
+    /// building an intermediate `String`. On error, `output` may already
+    /// contain part of the result. Missing arguments are handled as in
+    /// [`finish`](Self::finish).
+    ///
+    /// ```
+    /// use formatx::Template;
+    /// use std::fmt::Write;
+    ///
+    /// let template = Template::new("{name:>6}|").unwrap();
+    /// let mut output = String::from("name:");
+    /// template.render().named("name", &"Bob").write_to(&mut output).unwrap();
+    /// writeln!(output).unwrap();
+    /// assert_eq!(output, "name:   Bob|\n");
+    /// ```
+    pub fn write_to(&self, output: &mut impl fmt::Write) -> Result<(), Error> {
+        self.write_inner(output, &self.options)
+    }
+
+    /// **Lenient**: write the formatted output into `output`.
+    ///
+    /// Missing arguments are replaced with an empty string `""`, as in
+    /// [`finish_lenient`](Self::finish_lenient).
+    pub fn write_to_lenient(&self, output: &mut impl fmt::Write) -> Result<(), Error> {
+        self.write_inner(output, &self.lenient())
+    }
+
+    /// **Strict**: write the formatted output into an [`io::Write`] sink.
+    ///
+    /// Segments are written as they are rendered, so wrap unbuffered sinks
+    /// such as files in an [`io::BufWriter`]. A rendering failure is returned
+    /// as an [`io::Error`] of kind [`Other`](io::ErrorKind::Other) wrapping
+    /// the [`Error`].
+    ///
+    /// ```
+    /// use formatx::Template;
+    ///
+    /// let template = Template::new("{} {:?}").unwrap();
+    /// let mut output = Vec::new();
+    /// template.render().arg(&1).arg(&"a").write_io(&mut output).unwrap();
+    /// assert_eq!(output, b"1 \"a\"");
+    /// ```
+    pub fn write_io(&self, output: &mut impl io::Write) -> io::Result<()> {
+        self.write_io_inner(output, &self.options)
+    }
+
+    /// **Lenient**: write the formatted output into an [`io::Write`] sink.
+    ///
+    /// Missing arguments are replaced with an empty string `""`, as in
+    /// [`finish_lenient`](Self::finish_lenient).
+    pub fn write_io_lenient(&self, output: &mut impl io::Write) -> io::Result<()> {
+        self.write_io_inner(output, &self.lenient())
+    }
+
+    /// Take the error from the last failed [`Display`] formatting, if any.
+    ///
+    /// `Display` can only report [`fmt::Error`], so the detailed error is kept
+    /// here until retrieved. Only `write!` into a fallible sink surfaces that
+    /// `fmt::Error`: `format!`, `to_string` and `println!` panic on it instead,
+    /// before the error can be taken.
+    ///
+    /// ```
+    /// use formatx::{Error, Template};
+    /// use std::fmt::Write;
+    ///
+    /// let template = Template::new("{missing}").unwrap();
+    /// let renderer = template.render();
+    /// let mut output = String::new();
+    /// assert!(write!(output, "{renderer}").is_err());
+    /// assert!(matches!(renderer.take_error(), Some(Error::MissingArgument { .. })));
+    /// ```
+    pub fn take_error(&self) -> Option<Error> {
+        self.error.take()
+    }
+
+    fn set_slot(&mut self, slot: Slot, stored: Stored<'a>) -> &mut Self {
+        let count = self.template.parsed().names.len();
+        if slot.template != self.template.id() || slot.index >= count {
+            self.slot_error = Some(Error::ForeignSlot);
+            return self;
+        }
+        if self.slots.is_empty() {
+            self.slots.resize(count, None);
+        }
+        self.slots[slot.index] = Some(self.push(stored));
+        self
+    }
+
+    /// Store an argument, returning its positional index.
+    fn push(&mut self, stored: Stored<'a>) -> usize {
+        self.args.push(stored);
+        self.used.push(Cell::new(false));
+        self.args.len() - 1
+    }
+
+    fn push_named(&mut self, name: Cow<'a, str>, stored: Stored<'a>) -> &mut Self {
+        let index = self.push(stored);
+        self.named.push((name, index));
+        self
+    }
+
+    /// The renderer's options with missing arguments replaced by `""`.
+    fn lenient(&self) -> RenderOptions {
+        self.options.clone().with_missing(MissingPolicy::Empty)
+    }
+
+    fn render_inner(&self, options: &RenderOptions) -> Result<String, Error> {
+        let mut output = String::with_capacity(self.template.source().len());
+        self.write_inner(&mut output, options)?;
         Ok(output)
     }
+
+    fn write_inner(
+        &self,
+        output: &mut dyn fmt::Write,
+        options: &RenderOptions,
+    ) -> Result<(), Error> {
+        if let Some(error) = &self.slot_error {
+            return Err(error.clone());
+        }
+        let source = self.template.source();
+        if !options.deny_unused {
+            return format::render(output, source, self.template.parsed(), self, options);
+        }
+        self.used.iter().for_each(|used| used.set(false));
+        format::render(output, source, self.template.parsed(), self, options)?;
+        self.check_unused()
+    }
+
+    /// Return `Err(Error::UnusedArgument)` if any argument was not looked up
+    /// by the last render.
+    fn check_unused(&self) -> Result<(), Error> {
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+        for (index, _) in self.used.iter().enumerate().filter(|(_, used)| !used.get()) {
+            let slot = self.slots.iter().position(|slot| *slot == Some(index));
+            match self.named.iter().find(|(_, i)| *i == index) {
+                Some((name, _)) => named.push(name.to_string()),
+                None => match slot {
+                    Some(slot) => {
+                        let span = self.template.parsed().names[slot];
+                        named.push(self.template.resolve(span).to_string());
+                    }
+                    None => positional.push(index),
+                },
+            }
+        }
+        if positional.is_empty() && named.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedArgument { positional, named })
+        }
+    }
+
+    fn write_io_inner(
+        &self,
+        output: &mut dyn io::Write,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let mut adapter = IoAdapter {
+            inner: output,
+            error: None,
+        };
+        self.write_inner(&mut adapter, options)
+            .map_err(|e| adapter.error.take().unwrap_or_else(|| io::Error::other(e)))
+    }
+}
+
+/// Bridges [`fmt::Write`] to [`io::Write`], keeping the underlying I/O error.
+struct IoAdapter<'a> {
+    inner: &'a mut dyn io::Write,
+    error: Option<io::Error>,
+}
+
+impl fmt::Write for IoAdapter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+impl Renderer<'_> {
+    /// Names of the named arguments added to the renderer and used by the
+    /// template, for suggesting a replacement for a missing name.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        let template = self.template;
+        let used = template.parsed().names.iter();
+        self.named
+            .iter()
+            .map(|(name, _)| name.as_ref())
+            .chain(used.map(|span| template.resolve(*span)))
+    }
+
+    /// Look up a named argument by its template slot, falling back to a
+    /// by-name lookup if the slot was not set.
+    #[inline]
+    pub(crate) fn get_slot(&self, slot: usize, name: &str) -> Option<Arg<'_>> {
+        match self.slots.get(slot) {
+            Some(Some(index)) => {
+                self.used[*index].set(true);
+                Some(self.args[*index].arg())
+            }
+            _ => self.get_named(name),
+        }
+    }
+}
+
+impl Display for Renderer<'_> {
+    /// Writes straight into `f` unless an outer width or precision is set, in
+    /// which case the output is buffered and padded or truncated with
+    /// [`Formatter::pad`](fmt::Formatter::pad).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = if f.width().is_none() && f.precision().is_none() {
+            self.write_inner(f, &self.options)
+        } else {
+            self.render_inner(&self.options)
+                .and_then(|output| Ok(f.pad(&output)?))
+        };
+        result.map_err(|e| {
+            self.error.set(Some(e));
+            fmt::Error
+        })
+    }
+}
+
+impl ArgSource for Renderer<'_> {
+    fn get_positional(&self, index: usize) -> Option<Arg<'_>> {
+        match self.args.get(index) {
+            Some(stored) => {
+                self.used[index].set(true);
+                Some(stored.arg())
+            }
+            None => self
+                .sources
+                .iter()
+                .find_map(|source| source.get_positional(index)),
+        }
+    }
+
+    fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+        match self.named.iter().find(|(n, _)| n == name) {
+            Some((_, index)) => {
+                self.used[*index].set(true);
+                Some(self.args[*index].arg())
+            }
+            None => self
+                .sources
+                .iter()
+                .find_map(|source| source.get_named(name)),
+        }
+    }
 }
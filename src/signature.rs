@@ -0,0 +1,150 @@
+//! [`Signature`] - a structured description of the arguments a template uses.
+
+use crate::ast::{Argument, Count, CountParam, FormatString, FormatType, Precision, Segment, Span};
+use std::collections::BTreeMap;
+
+/// The arguments a [`Template`](crate::Template) needs, as returned by
+/// [`Template::signature`](crate::Template::signature).
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{FormatType, Template, UsageKind};
+///
+/// let template = Template::new("{} {name:>width$} {0:x} {:.*}").unwrap();
+/// let signature = template.signature();
+///
+/// // `{}`, `{0:x}` and the two implicit arguments of `{:.*}`.
+/// assert_eq!(signature.positional.len(), 3);
+/// assert_eq!(
+///     signature.positional[0].format_types(),
+///     [FormatType::Display, FormatType::LowerHex]
+/// );
+/// assert!(signature.positional[1].is_count());
+///
+/// assert!(signature.named["width"].is_count());
+/// assert_eq!(signature.named["name"].uses[0].kind, UsageKind::Value(FormatType::Display));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Signature {
+    /// Positional parameters by index. The length is the number of positional
+    /// arguments the template needs; indices that are skipped have no uses.
+    pub positional: Vec<Parameter>,
+    /// Named parameters by name.
+    pub named: BTreeMap<String, Parameter>,
+}
+
+/// A single argument used by a template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Parameter {
+    /// Every use of the argument, in source order.
+    pub uses: Vec<Usage>,
+}
+
+impl Parameter {
+    /// Returns the distinct format traits the argument is formatted with, in
+    /// order of first use.
+    pub fn format_types(&self) -> Vec<FormatType> {
+        let mut types = Vec::new();
+        for usage in &self.uses {
+            if let UsageKind::Value(ty) = usage.kind
+                && !types.contains(&ty)
+            {
+                types.push(ty);
+            }
+        }
+        types
+    }
+
+    /// Returns `true` if the argument is used as a width or precision.
+    pub fn is_count(&self) -> bool {
+        self.uses
+            .iter()
+            .any(|usage| matches!(usage.kind, UsageKind::Width | UsageKind::Precision))
+    }
+}
+
+/// One use of an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    /// How the argument is used.
+    pub kind: UsageKind,
+    /// Byte span of the placeholder containing the use.
+    pub span: Span,
+}
+
+/// How an argument is used by a placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+    /// Formatted as the placeholder's value with the given trait.
+    Value(FormatType),
+    /// Used as the width, e.g. `width$` in `{:width$}`.
+    Width,
+    /// Used as the precision, e.g. `prec$` in `{:.prec$}` or the argument
+    /// consumed by `{:.*}`.
+    Precision,
+}
+
+impl Signature {
+    pub(crate) fn new(source: &str, parsed: &FormatString) -> Self {
+        let mut signature = Self::default();
+        let mut implicit_pos = 0;
+
+        for segment in &parsed.segments {
+            let Segment::Placeholder(p) = segment else {
+                continue;
+            };
+            let span = p.span;
+
+            // `.*` consumes its implicit argument before the value's.
+            match &p.spec.precision {
+                Some(Precision::Star) => {
+                    signature.add_positional(implicit_pos, UsageKind::Precision, span);
+                    implicit_pos += 1;
+                }
+                Some(Precision::Count(Count::Param(param))) => {
+                    signature.add_param(source, param, UsageKind::Precision, span);
+                }
+                _ => {}
+            }
+
+            let kind = UsageKind::Value(p.spec.format_type);
+            match &p.argument {
+                Argument::Implicit => {
+                    signature.add_positional(implicit_pos, kind, span);
+                    implicit_pos += 1;
+                }
+                Argument::Positional(index) => signature.add_positional(*index, kind, span),
+                Argument::Named(name) => signature.add_named(source, name.span, kind, span),
+            }
+
+            if let Some(Count::Param(param)) = &p.spec.width {
+                signature.add_param(source, param, UsageKind::Width, span);
+            }
+        }
+        signature
+    }
+
+    fn add_param(&mut self, source: &str, param: &CountParam, kind: UsageKind, span: Span) {
+        match param {
+            CountParam::Positional(index) => self.add_positional(*index, kind, span),
+            CountParam::Named(name) => self.add_named(source, name.span, kind, span),
+        }
+    }
+
+    fn add_positional(&mut self, index: usize, kind: UsageKind, span: Span) {
+        if self.positional.len() <= index {
+            self.positional.resize_with(index + 1, Parameter::default);
+        }
+        self.positional[index].uses.push(Usage { kind, span });
+    }
+
+    fn add_named(&mut self, source: &str, name: Span, kind: UsageKind, span: Span) {
+        let name = name.slice(source);
+        self.named
+            .entry(name.to_string())
+            .or_default()
+            .uses
+            .push(Usage { kind, span });
+    }
+}
@@ -0,0 +1,114 @@
+//! The [`ArgSource`] trait - look up arguments by index or name.
+
+use crate::value::{Arg, ToArg, Value};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+/// A collection that a [`Renderer`](crate::Renderer) can query for arguments.
+///
+/// Implemented for `HashMap` and `BTreeMap` with string keys (named lookup),
+/// for slices and `Vec` (positional lookup), and for [`Value`] lists and maps.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::Template;
+/// use std::collections::HashMap;
+///
+/// let env = HashMap::from([("user", "alice"), ("shell", "zsh")]);
+/// let template = Template::new("{user} uses {shell}").unwrap();
+/// let result = template.render().source(&env).finish().unwrap();
+/// assert_eq!(result, "alice uses zsh");
+/// ```
+pub trait ArgSource {
+    /// Look up the positional argument at `index`.
+    fn get_positional(&self, index: usize) -> Option<Arg<'_>> {
+        let _ = index;
+        None
+    }
+
+    /// Look up the named argument `name`.
+    fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+        let _ = name;
+        None
+    }
+}
+
+/// An [`ArgSource`] with a fixed set of named arguments, known up front.
+///
+/// Usually implemented with `#[derive(FormatArgs)]`, available with the
+/// `derive` feature. Use [`Template::validate`](crate::Template::validate) to
+/// check a template against it before rendering.
+pub trait FormatArgs: ArgSource {
+    /// Names of all named arguments provided by this source.
+    const FIELDS: &'static [&'static str];
+}
+
+impl<T: ArgSource + ?Sized> ArgSource for &T {
+    fn get_positional(&self, index: usize) -> Option<Arg<'_>> {
+        (**self).get_positional(index)
+    }
+
+    fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+        (**self).get_named(name)
+    }
+}
+
+impl<K, V, S> ArgSource for HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: ToArg,
+    S: BuildHasher,
+{
+    fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+        HashMap::get(self, name).map(ToArg::to_arg)
+    }
+}
+
+impl<K, V> ArgSource for BTreeMap<K, V>
+where
+    K: Borrow<str> + Ord,
+    V: ToArg,
+{
+    fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+        BTreeMap::get(self, name).map(ToArg::to_arg)
+    }
+}
+
+impl<V: ToArg> ArgSource for [V] {
+    fn get_positional(&self, index: usize) -> Option<Arg<'_>> {
+        <[V]>::get(self, index).map(ToArg::to_arg)
+    }
+}
+
+impl<V: ToArg, const N: usize> ArgSource for [V; N] {
+    fn get_positional(&self, index: usize) -> Option<Arg<'_>> {
+        self.as_slice().get(index).map(ToArg::to_arg)
+    }
+}
+
+impl<V: ToArg> ArgSource for Vec<V> {
+    fn get_positional(&self, index: usize) -> Option<Arg<'_>> {
+        self.as_slice().get(index).map(ToArg::to_arg)
+    }
+}
+
+/// Lists are looked up by index and maps by name.
+impl ArgSource for Value {
+    fn get_positional(&self, index: usize) -> Option<Arg<'_>> {
+        match self {
+            Self::List(items) => items.get(index).map(Arg::from),
+            _ => None,
+        }
+    }
+
+    fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+        match self {
+            Self::Map(map) => map.get(name).map(Arg::from),
+            _ => None,
+        }
+    }
+}
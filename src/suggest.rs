@@ -0,0 +1,82 @@
+//! "Did you mean" suggestions for misspelled argument names.
+
+/// Returns up to three `candidates` close to `name`, closest first and in
+/// candidate order for equal distances.
+///
+/// Candidates within an edit distance of a third of the name's length (at
+/// least 1) are considered; `name` itself is never suggested.
+pub(crate) fn suggest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let max = name.chars().count().max(3) / 3;
+    let mut close: Vec<(usize, &str)> = Vec::new();
+    for candidate in candidates {
+        if candidate == name || close.iter().any(|(_, seen)| *seen == candidate) {
+            continue;
+        }
+        let distance = distance(name, candidate);
+        if distance <= max {
+            close.push((distance, candidate));
+        }
+    }
+    close.sort_by_key(|(distance, _)| *distance);
+    close
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// Edit distance counting insertions, deletions, substitutions and swaps of
+/// adjacent characters (optimal string alignment).
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Rows for the previous two prefixes of `a` and the current one.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("user", "user"), 0);
+        assert_eq!(distance("usre", "user"), 1);
+        assert_eq!(distance("usr", "user"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("naïve", "naive"), 1);
+    }
+
+    #[test]
+    fn suggestions() {
+        let names = ["user", "users", "age", "usre", "user"];
+        assert_eq!(suggest("usre", names), ["user"]);
+        assert_eq!(suggest("usr", names), ["user", "usre"]);
+        assert_eq!(suggest("agee", names), ["age"]);
+        assert!(suggest("x", names).is_empty());
+        assert!(suggest("email", names).is_empty());
+    }
+}
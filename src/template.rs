@@ -1,12 +1,19 @@
 //! The [`Template`] struct - parse once, inspect, render many times.
 
 use crate::{
-    ast::{Argument, FormatString, Segment, Span},
+    ast::{self, Argument, Count, CountParam, FormatString, Precision, Segment, Span, Visitor},
+    builder::TemplateBuilder,
+    cache::TemplateCache,
+    canonical,
     error::Error,
+    options::ParseOptions,
     parser,
     renderer::Renderer,
+    signature::Signature,
+    source::FormatArgs,
+    suggest,
 };
-use std::{fmt, str::FromStr};
+use std::{fmt, str::FromStr, sync::Arc};
 
 /// An owned, parsed format string that can be rendered many times with different arguments.
 ///
@@ -26,8 +33,8 @@ use std::{fmt, str::FromStr};
 /// assert_eq!(result, "Alice scored 95.7%");
 /// ```
 pub struct Template {
-    source: String,
-    parsed: FormatString,
+    source: Arc<str>,
+    parsed: Arc<FormatString>,
 }
 
 impl Template {
@@ -37,9 +44,103 @@ impl Template {
     pub fn new<S: Into<String>>(source: S) -> Result<Self, Error> {
         let source = source.into();
         let parsed = parser::parse(&source)?;
+        Ok(Self {
+            source: source.into(),
+            parsed: Arc::new(parsed),
+        })
+    }
+
+    /// Parse a format string with opt-in syntax extensions.
+    ///
+    /// See [`ParseOptions`] for the available extensions.
+    pub fn with_options<S: Into<String>>(source: S, options: ParseOptions) -> Result<Self, Error> {
+        let source = source.into();
+        let parsed = parser::parse_with(&source, &options)?;
+        Ok(Self {
+            source: source.into(),
+            parsed: Arc::new(parsed),
+        })
+    }
+
+    /// Parse a format string, collecting every parse error instead of
+    /// stopping at the first one.
+    ///
+    /// Returns the errors in source order together with the recovered
+    /// template, which contains every literal, escape and placeholder that
+    /// parsed. After an error the parser drops the text from the malformed
+    /// placeholder's `{` up to and including the next `}`, or up to the next
+    /// `{` if that comes first. An unmatched `}` is dropped on its own. The
+    /// [`source`](Self::source) is kept unchanged, so spans still point into it.
+    ///
+    /// Rendering the recovered template is supported: it renders like a
+    /// template without the dropped text. That output is not what the author
+    /// intended, so only use it for previews while there are errors.
+    ///
+    /// ```
+    /// use formatx::{Error, ParseErrorKind, ParseOptions, Template};
+    ///
+    /// let source = "Dear {name}, {amount:q} is due {date:.} }";
+    /// let (template, errors) = Template::parse_all_errors(source, ParseOptions::new());
+    /// let kinds: Vec<_> = errors
+    ///     .iter()
+    ///     .map(|e| match e {
+    ///         Error::Parse { kind, .. } => kind.clone(),
+    ///         _ => unreachable!(),
+    ///     })
+    ///     .collect();
+    /// assert_eq!(
+    ///     kinds,
+    ///     [
+    ///         ParseErrorKind::UnknownFormatType('q'),
+    ///         ParseErrorKind::ExpectedPrecision,
+    ///         ParseErrorKind::UnmatchedClose,
+    ///     ]
+    /// );
+    /// assert_eq!(template.placeholders(), ["name"]);
+    /// let preview = template.render().named("name", &"Ann").finish().unwrap();
+    /// assert_eq!(preview, "Dear Ann,  is due  ");
+    /// ```
+    pub fn parse_all_errors<S: Into<String>>(
+        source: S,
+        options: ParseOptions,
+    ) -> (Self, Vec<Error>) {
+        let source = source.into();
+        let (parsed, errors) = parser::parse_recovering(&source, &options);
+        (Self::from_parts(source, parsed), errors)
+    }
+
+    /// Parse a format string through the global [`TemplateCache`].
+    ///
+    /// If the cache is enabled and already holds `source`, the parsed template
+    /// is shared instead of parsing it again. With the cache disabled this is
+    /// equivalent to [`Template::new`].
+    ///
+    /// ```
+    /// use formatx::{Template, TemplateCache};
+    ///
+    /// TemplateCache::resize(64);
+    /// let a = Template::cached("{greeting}, {name}!").unwrap();
+    /// let b = Template::cached("{greeting}, {name}!").unwrap();
+    /// assert_eq!(a.source(), b.source());
+    /// ```
+    pub fn cached(source: impl AsRef<str>) -> Result<Self, Error> {
+        let (source, parsed) = TemplateCache::get(source.as_ref())?;
         Ok(Self { source, parsed })
     }
 
+    /// Create a [`TemplateBuilder`] to build a template from code.
+    pub fn builder() -> TemplateBuilder {
+        TemplateBuilder::new()
+    }
+
+    /// Wrap a source and its AST, which must describe the same template.
+    pub(crate) fn from_parts(source: String, parsed: FormatString) -> Self {
+        Self {
+            source: source.into(),
+            parsed: Arc::new(parsed),
+        }
+    }
+
     /// Create a [`Renderer`] to format this template with arguments.
     ///
     /// The renderer collects arguments and produces the formatted output.
@@ -51,9 +152,9 @@ impl Template {
     pub fn contains(&self, name: &str) -> bool {
         self.parsed.segments.iter().any(|seg| {
             if let Segment::Placeholder(p) = seg
-                && let Argument::Named(span) = &p.argument
+                && let Argument::Named(arg) = &p.argument
             {
-                return self.resolve(*span) == name;
+                return self.resolve(arg.span) == name;
             }
             false
         })
@@ -66,31 +167,140 @@ impl Template {
             .iter()
             .filter_map(|seg| {
                 if let Segment::Placeholder(p) = seg
-                    && let Argument::Named(span) = &p.argument
+                    && let Argument::Named(name) = &p.argument
                 {
-                    return Some(self.resolve(*span));
+                    return Some(self.resolve(name.span));
                 }
                 None
             })
             .collect::<Vec<_>>()
     }
 
+    /// Describe every argument the template uses: positional indices
+    /// (including implicit `{}` and `.*`), named arguments, whether they are
+    /// used as width/precision counts, the format traits they need and where
+    /// they are used.
+    pub fn signature(&self) -> Signature {
+        Signature::new(&self.source, &self.parsed)
+    }
+
+    /// Check that every named argument the template uses, including `$`
+    /// width/precision parameters, is provided by `T`.
+    ///
+    /// Returns `Err(Error::MissingArgument)` for the first name not listed in
+    /// [`FormatArgs::FIELDS`]. Positional arguments are not checked.
+    pub fn validate<T: FormatArgs + ?Sized>(&self) -> Result<(), Error> {
+        for seg in &self.parsed.segments {
+            let Segment::Placeholder(p) = seg else {
+                continue;
+            };
+            let precision = match &p.spec.precision {
+                Some(Precision::Count(count)) => Some(count),
+                _ => None,
+            };
+            let count_params = [p.spec.width.as_ref(), precision]
+                .into_iter()
+                .flatten()
+                .filter_map(|count| match count {
+                    Count::Param(CountParam::Named(name)) => Some(name.span),
+                    _ => None,
+                });
+            let argument = match &p.argument {
+                Argument::Named(name) => Some(name.span),
+                _ => None,
+            };
+            for span in argument.into_iter().chain(count_params) {
+                let name = self.resolve(span);
+                if !T::FIELDS.contains(&name) {
+                    return Err(Error::MissingArgument {
+                        name: name.to_string(),
+                        span: p.span,
+                        suggestions: suggest::suggest(name, T::FIELDS.iter().copied()),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the [`Slot`] of a named argument used by the template, for
+    /// setting it with [`Renderer::slot`] without comparing names.
+    ///
+    /// Returns `None` if no placeholder or `$` count parameter uses `name`.
+    pub fn slot(&self, name: &str) -> Option<Slot> {
+        self.parsed
+            .names
+            .iter()
+            .position(|span| self.resolve(*span) == name)
+            .map(|index| Slot {
+                index,
+                template: self.id(),
+            })
+    }
+
+    /// Identifies the parsed template, which templates from the cache share.
+    pub(crate) fn id(&self) -> u64 {
+        self.parsed.id
+    }
+
     /// Returns the original format string.
     pub fn source(&self) -> &str {
         &self.source
     }
 
-    /// Returns a reference to the parsed AST.
-    pub(crate) fn parsed(&self) -> &FormatString {
+    /// Print the template in a normalised form, for comparing templates that
+    /// differ only in notation.
+    ///
+    /// Implicit `{}` and `.*` become explicit positional indices, spec flags
+    /// without effect and empty specs are dropped and spec parts are written
+    /// in the standard order. The result parses back to an equivalent
+    /// template; templates with default values need
+    /// [`ParseOptions::with_defaults`].
+    ///
+    /// ```
+    /// use formatx::Template;
+    ///
+    /// let template = Template::new("{{{}}} {:} {name: >-5} {:.*} {0:<}").unwrap();
+    /// assert_eq!(template.to_canonical_string(), "{{{0}}} {1} {name:>5} {3:.2$} {0}");
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        canonical::to_canonical_string(&self.source, &self.parsed)
+    }
+
+    /// Returns the parsed [AST](crate::ast).
+    pub fn parsed(&self) -> &FormatString {
         &self.parsed
     }
 
-    /// Resolve a [`Span`] to a string slice from the source.
-    pub(crate) fn resolve(&self, span: Span) -> &str {
-        &self.source[span.start..span.end]
+    /// Walk the parsed [AST](crate::ast) with a [`Visitor`].
+    pub fn visit<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        ast::walk(visitor, &self.parsed, &self.source);
+    }
+
+    /// Resolve a [`Span`] from the AST to a string slice of the source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `span` is out of bounds for the source.
+    pub fn resolve(&self, span: Span) -> &str {
+        span.slice(&self.source)
     }
 }
 
+/// A pre-resolved named argument of a [`Template`], obtained from
+/// [`Template::slot`].
+///
+/// Slots are only meaningful for the template that produced them, or one
+/// sharing its parsed form through the [`TemplateCache`]. Rendering with a
+/// slot from another template returns [`Error::ForeignSlot`]; this includes
+/// a cached template that was evicted and parsed again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot {
+    pub(crate) index: usize,
+    /// The [`Template::id`] of the template that produced the slot.
+    pub(crate) template: u64,
+}
+
 impl fmt::Display for Template {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(&self.source)
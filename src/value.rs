@@ -1,12 +1,708 @@
-//! The [`FormatValue`] marker trait.
+//! The [`FormatValue`] marker trait, the capability-aware [`Arg`] type and the
+//! owned [`Value`] type.
 
-use std::fmt::{Debug, Display};
+use crate::{ast::FormatType, source::ArgSource};
+use std::{
+    borrow::{Borrow, Cow},
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Binary, Debug, Display, LowerExp, LowerHex, Octal, UpperExp, UpperHex},
+    hash::{BuildHasher, Hash},
+    num::{Saturating, Wrapping},
+};
 
 /// Marker trait for values that can be formatted at runtime.
 ///
 /// Blanket-implemented for all `T: Display + Debug`, which covers the vast
 /// majority of Rust types (`i32`, `f64`, `String`, `&str`, `bool`, `char`,
 /// custom types with `#[derive(Debug)]` and a `Display` impl, etc.).
+///
+/// Arguments of [`formatx!`](crate::formatx), [`formatxl!`](crate::formatxl)
+/// and `#[derive(FormatArgs)]` are converted with [`ToArg`] when their type
+/// implements it, so primitive integers also support the `{:o}`, `{:x}`,
+/// `{:X}` and `{:b}` format types, and primitive integers and floats support
+/// `{:e}` and `{:E}`. Use [`Arg`] for values implementing a different set of
+/// traits.
 pub trait FormatValue: Display + Debug {}
 
 impl<T: Display + Debug> FormatValue for T {}
+
+/// A runtime argument that records which formatting traits it supports.
+///
+/// [`Renderer::arg`](crate::Renderer::arg) builds one with [`Arg::new`]. Use
+/// [`Renderer::arg_with`](crate::Renderer::arg_with) to pass values that don't
+/// implement both `Display` and `Debug`, or that implement extra traits.
+/// Placeholders asking for a trait the argument lacks produce
+/// [`Error::UnsupportedTrait`](crate::Error::UnsupportedTrait) at render time.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{Arg, FormatType, Template};
+///
+/// struct Id(u32);
+///
+/// impl std::fmt::Debug for Id {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "Id({})", self.0)
+///     }
+/// }
+///
+/// let id = Id(7);
+/// let arg = Arg::debug(&id).with_lower_hex(&id.0);
+/// assert!(arg.supports(FormatType::Debug));
+/// assert!(!arg.supports(FormatType::Display));
+///
+/// let template = Template::new("{0:?} = {0:#x}").unwrap();
+/// let result = template.render().arg_with(arg).finish().unwrap();
+/// assert_eq!(result, "Id(7) = 0x7");
+/// ```
+#[derive(Default, Clone, Copy)]
+pub struct Arg<'a> {
+    pub(crate) display: Option<&'a dyn Display>,
+    debug: Option<&'a dyn Debug>,
+    octal: Option<&'a dyn Octal>,
+    lower_hex: Option<&'a dyn LowerHex>,
+    upper_hex: Option<&'a dyn UpperHex>,
+    binary: Option<&'a dyn Binary>,
+    lower_exp: Option<&'a dyn LowerExp>,
+    upper_exp: Option<&'a dyn UpperExp>,
+    pointer: Option<*const ()>,
+    /// Nested arguments for `{arg.field}` and `{arg[0]}` paths.
+    pub(crate) fields: Option<&'a dyn ArgSource>,
+}
+
+impl<'a> Arg<'a> {
+    /// An argument supporting `Display` and `Debug`.
+    ///
+    /// Use [`ToArg::to_arg`] to also support the radix and exponent traits of
+    /// primitive numbers.
+    #[inline]
+    pub fn new<T: FormatValue>(value: &'a T) -> Self {
+        Self::display(value).with_debug(value)
+    }
+
+    /// An argument supporting `Display`, `Debug` and the integer radix and
+    /// exponent traits.
+    #[inline]
+    pub fn integer<T>(value: &'a T) -> Self
+    where
+        T: FormatValue + Octal + LowerHex + UpperHex + Binary + LowerExp + UpperExp,
+    {
+        Self::new(value)
+            .with_radix(value)
+            .with_lower_exp(value)
+            .with_upper_exp(value)
+    }
+
+    /// An argument supporting `Display`, `Debug` and the exponent traits.
+    #[inline]
+    pub fn float<T: FormatValue + LowerExp + UpperExp>(value: &'a T) -> Self {
+        Self::new(value).with_lower_exp(value).with_upper_exp(value)
+    }
+
+    /// An argument supporting only `Display`.
+    #[inline]
+    pub fn display<T: Display>(value: &'a T) -> Self {
+        Self::default().with_display(value)
+    }
+
+    /// An argument supporting only `Debug`.
+    #[inline]
+    pub fn debug<T: Debug>(value: &'a T) -> Self {
+        Self::default().with_debug(value)
+    }
+
+    /// An argument supporting only `Pointer`, formatted as the address of `value`.
+    #[inline]
+    pub fn pointer<T>(value: &'a T) -> Self {
+        Self::default().with_pointer(value)
+    }
+
+    /// Add `Display` support.
+    #[inline]
+    pub fn with_display<T: Display>(mut self, value: &'a T) -> Self {
+        self.display = Some(value);
+        self
+    }
+
+    /// Add `Debug` support.
+    #[inline]
+    pub fn with_debug<T: Debug>(mut self, value: &'a T) -> Self {
+        self.debug = Some(value);
+        self
+    }
+
+    /// Add `Octal` support.
+    #[inline]
+    pub fn with_octal<T: Octal>(mut self, value: &'a T) -> Self {
+        self.octal = Some(value);
+        self
+    }
+
+    /// Add `LowerHex` support.
+    #[inline]
+    pub fn with_lower_hex<T: LowerHex>(mut self, value: &'a T) -> Self {
+        self.lower_hex = Some(value);
+        self
+    }
+
+    /// Add `UpperHex` support.
+    #[inline]
+    pub fn with_upper_hex<T: UpperHex>(mut self, value: &'a T) -> Self {
+        self.upper_hex = Some(value);
+        self
+    }
+
+    /// Add `Binary` support.
+    #[inline]
+    pub fn with_binary<T: Binary>(mut self, value: &'a T) -> Self {
+        self.binary = Some(value);
+        self
+    }
+
+    /// Add `LowerExp` support.
+    #[inline]
+    pub fn with_lower_exp<T: LowerExp>(mut self, value: &'a T) -> Self {
+        self.lower_exp = Some(value);
+        self
+    }
+
+    /// Add `UpperExp` support.
+    #[inline]
+    pub fn with_upper_exp<T: UpperExp>(mut self, value: &'a T) -> Self {
+        self.upper_exp = Some(value);
+        self
+    }
+
+    /// Add `Octal`, `LowerHex`, `UpperHex` and `Binary` support.
+    #[inline]
+    pub fn with_radix<T: Octal + LowerHex + UpperHex + Binary>(self, value: &'a T) -> Self {
+        self.with_octal(value)
+            .with_lower_hex(value)
+            .with_upper_hex(value)
+            .with_binary(value)
+    }
+
+    /// Add `Pointer` support, formatted as the address of `value`.
+    ///
+    /// For unsized values this is the data address alone, without the
+    /// metadata `std` prints for wide pointers.
+    #[inline]
+    pub fn with_pointer<T: ?Sized>(mut self, value: &'a T) -> Self {
+        self.pointer = Some(value as *const T as *const ());
+        self
+    }
+
+    /// Add nested lookups, used to resolve `{arg.field}` and `{arg[0]}` paths.
+    ///
+    /// ```
+    /// use formatx::{Arg, Template};
+    /// use std::collections::HashMap;
+    ///
+    /// let user = HashMap::from([("name", "Alice")]);
+    /// let template = Template::new("Hi {user.name}").unwrap();
+    /// let result = template.render()
+    ///     .named_with("user", Arg::default().with_fields(&user))
+    ///     .finish()
+    ///     .unwrap();
+    /// assert_eq!(result, "Hi Alice");
+    /// ```
+    #[inline]
+    pub fn with_fields<T: ArgSource>(mut self, value: &'a T) -> Self {
+        self.fields = Some(value);
+        self
+    }
+
+    /// Returns `true` if the argument can be formatted with `format_type`.
+    pub fn supports(&self, format_type: FormatType) -> bool {
+        match format_type {
+            FormatType::Display => self.display.is_some(),
+            FormatType::Debug | FormatType::DebugLowerHex | FormatType::DebugUpperHex => {
+                self.debug.is_some()
+            }
+            FormatType::Octal => self.octal.is_some(),
+            FormatType::LowerHex => self.lower_hex.is_some(),
+            FormatType::UpperHex => self.upper_hex.is_some(),
+            FormatType::Binary => self.binary.is_some(),
+            FormatType::LowerExp => self.lower_exp.is_some(),
+            FormatType::UpperExp => self.upper_exp.is_some(),
+            FormatType::Pointer => self.pointer.is_some(),
+        }
+    }
+
+    /// Format with the trait selected by `format_type`.
+    ///
+    /// Returns `None` if the argument does not support that trait.
+    pub(crate) fn fmt(
+        &self,
+        format_type: FormatType,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        match format_type {
+            FormatType::Display => self.display.map(|v| v.fmt(f)),
+            FormatType::Debug | FormatType::DebugLowerHex | FormatType::DebugUpperHex => {
+                self.debug.map(|v| v.fmt(f))
+            }
+            FormatType::Octal => self.octal.map(|v| v.fmt(f)),
+            FormatType::LowerHex => self.lower_hex.map(|v| v.fmt(f)),
+            FormatType::UpperHex => self.upper_hex.map(|v| v.fmt(f)),
+            FormatType::Binary => self.binary.map(|v| v.fmt(f)),
+            FormatType::LowerExp => self.lower_exp.map(|v| v.fmt(f)),
+            FormatType::UpperExp => self.upper_exp.map(|v| v.fmt(f)),
+            FormatType::Pointer => self.pointer.map(|v| fmt::Pointer::fmt(&v, f)),
+        }
+    }
+}
+
+impl Debug for Arg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const TYPES: [FormatType; 9] = [
+            FormatType::Display,
+            FormatType::Debug,
+            FormatType::Octal,
+            FormatType::LowerHex,
+            FormatType::UpperHex,
+            FormatType::Binary,
+            FormatType::LowerExp,
+            FormatType::UpperExp,
+            FormatType::Pointer,
+        ];
+        f.debug_struct("Arg")
+            .field(
+                "supports",
+                &TYPES
+                    .into_iter()
+                    .filter(|ty| self.supports(*ty))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// An owned, dynamically typed value for data-driven rendering.
+///
+/// Every variant formats exactly like the Rust value it wraps, and only
+/// advertises the formatting traits that value implements (e.g. `{:x}` works
+/// for [`Value::Int`] but not for [`Value::String`]). Lists and maps format as
+/// `[a, b]` and `{key: value}` with `{}`, and like `Vec`/`BTreeMap` with `{:?}`.
+///
+/// Signed integers remember the bit width of the type they were converted
+/// from, so `{:x}` prints `-1i8` as `ff` like `std::fmt` does. There is no
+/// conversion from `i128` or `u128`; `to_value` (behind the `serde` feature) stores them
+/// like `i64` and `u64`, and returns an error if they don't fit.
+///
+/// # Examples
+///
+/// ```
+/// use formatx::{Template, Value};
+///
+/// let template = Template::new("{name} owns {count:#x} items: {tags:?}").unwrap();
+/// let result = template.render()
+///     .named_value("name", "Alice")
+///     .named_value("count", 255)
+///     .named_value("tags", vec!["a", "b"])
+///     .finish()
+///     .unwrap();
+/// assert_eq!(result, r#"Alice owns 0xff items: ["a", "b"]"#);
+/// ```
+#[derive(Default, Clone, PartialEq)]
+pub enum Value {
+    /// The absence of a value, formatted as `null`.
+    #[default]
+    Null,
+    Bool(bool),
+    /// A signed integer, formatted with the radix traits as the two's
+    /// complement of its `bits` lowest bits.
+    Int {
+        value: i64,
+        bits: u32,
+    },
+    UInt(u64),
+    Float(f64),
+    Char(char),
+    String(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Returns `true` if the value can be formatted with `format_type`.
+    pub fn supports(&self, format_type: FormatType) -> bool {
+        Arg::from(self).supports(format_type)
+    }
+}
+
+impl<'a> From<&'a Value> for Arg<'a> {
+    fn from(value: &'a Value) -> Self {
+        let arg = Arg::display(value).with_debug(value);
+        match value {
+            Value::List(_) | Value::Map(_) => arg.with_fields(value),
+            Value::Int { .. } | Value::UInt(_) => arg
+                .with_radix(value)
+                .with_lower_exp(value)
+                .with_upper_exp(value),
+            Value::Float(_) => arg.with_lower_exp(value).with_upper_exp(value),
+            _ => arg,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.pad("null"),
+            Self::Bool(v) => Display::fmt(v, f),
+            Self::Int { value, .. } => Display::fmt(value, f),
+            Self::UInt(v) => Display::fmt(v, f),
+            Self::Float(v) => Display::fmt(v, f),
+            Self::Char(v) => Display::fmt(v, f),
+            Self::String(v) => Display::fmt(v, f),
+            Self::List(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_str("]")
+            }
+            Self::Map(map) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+/// Formatted like the wrapped Rust value, not like the enum.
+impl Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.pad("null"),
+            Self::Bool(v) => Debug::fmt(v, f),
+            Self::Int { value, .. } => Debug::fmt(value, f),
+            Self::UInt(v) => Debug::fmt(v, f),
+            Self::Float(v) => Debug::fmt(v, f),
+            Self::Char(v) => Debug::fmt(v, f),
+            Self::String(v) => Debug::fmt(v, f),
+            Self::List(items) => f.debug_list().entries(items).finish(),
+            Self::Map(map) => f.debug_map().entries(map).finish(),
+        }
+    }
+}
+
+macro_rules! value_traits {
+    (integer: [$($int_trait:ident),*], number: [$($num_trait:ident),*] $(,)?) => {
+        $(impl $int_trait for Value {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Self::Int { value, bits } if *bits < i64::BITS => {
+                        $int_trait::fmt(&(*value as u64 & ((1 << bits) - 1)), f)
+                    }
+                    Self::Int { value, .. } => $int_trait::fmt(value, f),
+                    Self::UInt(v) => $int_trait::fmt(v, f),
+                    _ => Err(fmt::Error),
+                }
+            }
+        })*
+        $(impl $num_trait for Value {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Self::Int { value, .. } => $num_trait::fmt(value, f),
+                    Self::UInt(v) => $num_trait::fmt(v, f),
+                    Self::Float(v) => $num_trait::fmt(v, f),
+                    _ => Err(fmt::Error),
+                }
+            }
+        })*
+    };
+}
+
+value_traits! {
+    integer: [Octal, LowerHex, UpperHex, Binary],
+    number: [LowerExp, UpperExp],
+}
+
+macro_rules! value_from {
+    ($($variant:ident($target:ty): [$($source:ty),*]),* $(,)?) => {
+        $($(impl From<$source> for Value {
+            #[inline]
+            fn from(value: $source) -> Self {
+                Self::$variant(<$target>::from(value))
+            }
+        })*)*
+    };
+}
+
+value_from! {
+    Bool(bool): [bool],
+    UInt(u64): [u8, u16, u32, u64],
+    Float(f64): [f64],
+    Char(char): [char],
+    String(String): [String, &str],
+}
+
+macro_rules! value_from_int {
+    ($($source:ty),* $(,)?) => {
+        $(impl From<$source> for Value {
+            #[inline]
+            fn from(value: $source) -> Self {
+                Self::Int {
+                    value: value as i64,
+                    bits: <$source>::BITS,
+                }
+            }
+        })*
+    };
+}
+
+value_from_int!(i8, i16, i32, i64, isize);
+
+impl From<usize> for Value {
+    #[inline]
+    fn from(value: usize) -> Self {
+        Self::UInt(value as u64)
+    }
+}
+
+/// Converts through the shortest `Display` representation, so `0.1f32`
+/// becomes `0.1` rather than `0.10000000149011612`.
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Self::Float(value.to_string().parse().unwrap_or(f64::from(value)))
+    }
+}
+
+impl From<()> for Value {
+    #[inline]
+    fn from(_: ()) -> Self {
+        Self::Null
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(value: Vec<T>) -> Self {
+        Self::List(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    fn from(value: BTreeMap<K, V>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<HashMap<K, V>> for Value {
+    fn from(value: HashMap<K, V>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::List(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::Map(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+/// Conversion into an [`Arg`], used by [`Renderer::arg`](crate::Renderer::arg)
+/// and friends and by [`ArgSource`](crate::ArgSource) implementations for
+/// collections.
+///
+/// Implemented for primitives, strings, [`Value`], [`Arg`] and nested
+/// collections (which support `Debug` and path lookups), and through
+/// references, [`Box`], [`Wrapping`] and [`Saturating`]. Primitive numbers
+/// support the same formatting traits as in `std::fmt`; references and boxes
+/// add `Pointer`. For other types, build the argument with [`Arg::new`] or
+/// the other constructors, or implement `ToArg` in terms of them.
+///
+/// ```
+/// use formatx::{Arg, Template, ToArg};
+///
+/// let template = Template::new("{addr:#06x} {id}").unwrap();
+/// let result = template.render().named("addr", &0xbeu8).named("id", &Id(7)).finish().unwrap();
+/// assert_eq!(result, "0x00be #7");
+///
+/// #[derive(Debug)]
+/// struct Id(u32);
+///
+/// impl std::fmt::Display for Id {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "#{}", self.0)
+///     }
+/// }
+///
+/// impl ToArg for Id {
+///     fn to_arg(&self) -> Arg<'_> {
+///         Arg::new(self)
+///     }
+/// }
+/// ```
+pub trait ToArg {
+    /// Borrow `self` as an argument.
+    fn to_arg(&self) -> Arg<'_>;
+}
+
+impl ToArg for Arg<'_> {
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        *self
+    }
+}
+
+impl ToArg for Value {
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        Arg::from(self)
+    }
+}
+
+impl<V: ToArg + Debug> ToArg for Vec<V> {
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        Arg::debug(self).with_fields(self)
+    }
+}
+
+impl<K, V, S> ToArg for HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq + Debug,
+    V: ToArg + Debug,
+    S: BuildHasher,
+{
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        Arg::debug(self).with_fields(self)
+    }
+}
+
+impl<K: Borrow<str> + Ord + Debug, V: ToArg + Debug> ToArg for BTreeMap<K, V> {
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        Arg::debug(self).with_fields(self)
+    }
+}
+
+impl<T: ToArg + ?Sized> ToArg for Box<T> {
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        (**self).to_arg().with_pointer(&**self)
+    }
+}
+
+impl<T: ToArg + ?Sized> ToArg for &T {
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        (**self).to_arg().with_pointer(*self)
+    }
+}
+
+impl<T: ToArg + ?Sized> ToArg for &mut T {
+    #[inline]
+    fn to_arg(&self) -> Arg<'_> {
+        (**self).to_arg().with_pointer(&**self)
+    }
+}
+
+macro_rules! to_arg {
+    ($constructor:ident: [$($ty:ty),* $(,)?]) => {
+        $(impl ToArg for $ty {
+            #[inline]
+            fn to_arg(&self) -> Arg<'_> {
+                Arg::$constructor(self)
+            }
+        })*
+    };
+}
+
+to_arg!(integer: [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize]);
+to_arg!(float: [f32, f64]);
+to_arg!(new: [bool, char, &str, String, Cow<'_, str>]);
+
+// The exponent traits, which `std` doesn't implement for the wrappers, format
+// the wrapped number.
+macro_rules! to_arg_wrapper {
+    ($($wrapper:ident),* $(,)?) => {
+        $(impl<T: LowerExp + UpperExp> ToArg for $wrapper<T>
+        where
+            $wrapper<T>: FormatValue + Octal + LowerHex + UpperHex + Binary,
+        {
+            #[inline]
+            fn to_arg(&self) -> Arg<'_> {
+                Arg::new(self)
+                    .with_radix(self)
+                    .with_lower_exp(&self.0)
+                    .with_upper_exp(&self.0)
+            }
+        })*
+    };
+}
+
+to_arg_wrapper!(Wrapping, Saturating);
+
+/// Autoref dispatch used by the macros and `#[derive(FormatArgs)]`: converts
+/// with [`ToArg`] if the type implements it, and with [`Arg::new`] otherwise.
+#[doc(hidden)]
+pub struct Autoref<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaToArg<'a> {
+    fn __formatx_arg(&self) -> Arg<'a>;
+}
+
+impl<'a, T: ToArg + ?Sized> ViaToArg<'a> for &Autoref<'a, T> {
+    #[inline]
+    fn __formatx_arg(&self) -> Arg<'a> {
+        self.0.to_arg()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaFormatValue<'a> {
+    fn __formatx_arg(&self) -> Arg<'a>;
+}
+
+impl<'a, T: FormatValue> ViaFormatValue<'a> for Autoref<'a, T> {
+    #[inline]
+    fn __formatx_arg(&self) -> Arg<'a> {
+        Arg::new(self.0)
+    }
+}
+
+/// An argument stored by a [`Renderer`](crate::Renderer), either borrowed or owned.
+pub(crate) enum Stored<'a> {
+    Borrowed(Arg<'a>),
+    Owned(Value),
+}
+
+impl Stored<'_> {
+    #[inline]
+    pub fn arg(&self) -> Arg<'_> {
+        match self {
+            Self::Borrowed(arg) => *arg,
+            Self::Owned(value) => Arg::from(value),
+        }
+    }
+}
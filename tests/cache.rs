@@ -0,0 +1,151 @@
+use formatx::{Error, Template, TemplateCache, formatx, formatxl};
+use std::sync::{Mutex, MutexGuard};
+
+// The cache is global, so tests touching it must not interleave.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn lock(capacity: usize) -> MutexGuard<'static, ()> {
+    let guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    TemplateCache::resize(capacity);
+    TemplateCache::clear();
+    guard
+}
+
+#[test]
+fn disabled_by_default() {
+    let _guard = lock(0);
+    assert_eq!(TemplateCache::capacity(), 0);
+    for _ in 0..3 {
+        assert_eq!(formatx!("{} {}", 1, 2).unwrap(), "1 2");
+    }
+    let stats = TemplateCache::stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (0, 0, 0));
+}
+
+#[test]
+fn hits_and_misses() {
+    let _guard = lock(8);
+    for i in 0..4 {
+        assert_eq!(formatx!("{}-{x}", i, x = "a").unwrap(), format!("{i}-a"));
+        assert_eq!(formatxl!("{}-{y}", i).unwrap(), format!("{i}-"));
+    }
+    let t = Template::cached(String::from("{}-{x}")).unwrap();
+    assert_eq!(t.render().arg(&9).named("x", &"b").finish().unwrap(), "9-b");
+
+    let stats = TemplateCache::stats();
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.hits, 7);
+    assert_eq!(stats.len, 2);
+    assert_eq!(stats.capacity, 8);
+    assert!((stats.hit_rate() - 7.0 / 9.0).abs() < f64::EPSILON);
+
+    TemplateCache::clear();
+    let stats = TemplateCache::stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (0, 0, 0));
+    assert_eq!(stats.hit_rate(), 0.0);
+}
+
+#[test]
+fn evicts_least_recently_used() {
+    let _guard = lock(2);
+    Template::cached("a{}").unwrap();
+    Template::cached("b{}").unwrap();
+    Template::cached("a{}").unwrap();
+    Template::cached("c{}").unwrap();
+    assert_eq!(TemplateCache::stats().len, 2);
+
+    // `b{}` was evicted, `a{}` and `c{}` are still cached.
+    Template::cached("a{}").unwrap();
+    Template::cached("c{}").unwrap();
+    assert_eq!(TemplateCache::stats().hits, 3);
+    Template::cached("b{}").unwrap();
+    assert_eq!(TemplateCache::stats().misses, 4);
+
+    TemplateCache::resize(1);
+    assert_eq!(TemplateCache::stats().len, 1);
+    Template::cached("b{}").unwrap();
+    assert_eq!(TemplateCache::stats().hits, 4);
+
+    TemplateCache::resize(0);
+    assert_eq!(TemplateCache::stats().len, 0);
+}
+
+#[test]
+fn errors_not_cached() {
+    let _guard = lock(4);
+    for _ in 0..2 {
+        assert!(matches!(Template::cached("{"), Err(Error::Parse { .. })));
+    }
+    let stats = TemplateCache::stats();
+    assert_eq!((stats.misses, stats.len), (2, 0));
+}
+
+#[test]
+fn shared_across_threads() {
+    let _guard = lock(16);
+    let handles = (0..4)
+        .map(|t| {
+            std::thread::spawn(move || {
+                for i in 0..50 {
+                    assert_eq!(formatx!("{}:{}", t, i).unwrap(), format!("{t}:{i}"));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let stats = TemplateCache::stats();
+    assert_eq!(stats.hits + stats.misses, 200);
+    assert_eq!(stats.len, 1);
+}
+
+#[test]
+fn slots_shared_between_cached_templates() {
+    let _guard = lock(8);
+    let a = Template::cached("{x} {y}").unwrap();
+    let b = Template::cached("{x} {y}").unwrap();
+    let slot = a.slot("y").unwrap();
+    let result = b.render().named("x", &1).slot(slot, &2).finish().unwrap();
+    assert_eq!(result, "1 2");
+}
+
+#[test]
+fn concurrent_misses_share_template() {
+    let _guard = lock(4);
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(8));
+    let templates = (0..8)
+        .map(|_| {
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                Template::cached("{name} {n}").unwrap()
+            })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    let slot = templates[0].slot("n").unwrap();
+    for t in &templates {
+        let result = t.render().named("name", &"a").slot(slot, &1).finish();
+        assert_eq!(result.unwrap(), "a 1");
+    }
+    let stats = TemplateCache::stats();
+    assert_eq!(stats.hits + stats.misses, 8);
+    assert_eq!(stats.len, 1);
+}
+
+#[test]
+fn slots_from_evicted_templates() {
+    let _guard = lock(1);
+    let a = Template::cached("{a} {b}").unwrap();
+    let slot = a.slot("b").unwrap();
+    Template::cached("{c}").unwrap();
+    let b = Template::cached("{a} {b}").unwrap();
+    let result = b.render().named("a", &1).slot(slot, &2).finish();
+    assert_eq!(result, Err(Error::ForeignSlot));
+    // The evicted template itself still accepts its slots.
+    let result = a.render().named("a", &1).slot(slot, &2).finish();
+    assert_eq!(result.unwrap(), "1 2");
+}
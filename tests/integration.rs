@@ -1,4 +1,13 @@
-use formatx::{Error, FormatType, Template, formatx, formatxl};
+#![allow(clippy::approx_constant)]
+
+use formatx::{
+    Arg, ArgSource, Error, FormatType, MissingPolicy, ParseOptions, RenderOptions, Span, Spec,
+    Template, Usage, UsageKind, Value, formatx, formatxl,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
 
 macro_rules! assert_fmt {
     ($spec:literal $(, $arg:expr)* $(,)?) => {
@@ -166,9 +175,882 @@ fn lenient_all_present() {
     assert_eq!(formatxl!("{} {}", "a", "b").unwrap(), "a b");
 }
 
+#[test]
+fn lenient_missing_count() {
+    assert_eq!(formatxl!("[{:w$}] [{:.*}] [{:1$}]", 1).unwrap(), "[] [] []");
+}
+
+#[test]
+fn missing_policy() {
+    let t = Template::new("{a} {b:>4} {0:w$} {:.*}|").unwrap();
+    let render = |policy| {
+        t.render()
+            .named("a", &1)
+            .options(RenderOptions::new().with_missing(policy))
+            .finish()
+    };
+    assert!(matches!(
+        render(MissingPolicy::Error),
+        Err(Error::MissingArgument { ref name, .. }) if name == "b"
+    ));
+    assert_eq!(render(MissingPolicy::Empty).unwrap(), "1   |");
+    assert_eq!(
+        render(MissingPolicy::Keep).unwrap(),
+        "1 {b:>4} {0:w$} {:.*}|"
+    );
+    assert_eq!(
+        render(MissingPolicy::Marker("<?>".into())).unwrap(),
+        "1 <?> <?> <?>|"
+    );
+    let callback = MissingPolicy::callback(|name, span| format!("<{name}@{}>", span.start));
+    assert_eq!(render(callback).unwrap(), "1 <b@4> <w@11> <1@18>|");
+}
+
+#[test]
+fn missing_policy_other_outputs() {
+    use std::fmt::Write;
+
+    let t = Template::new("{x}-{y}").unwrap();
+    let mut renderer = t.render();
+    renderer
+        .named("x", &1)
+        .options(RenderOptions::new().with_missing(MissingPolicy::Keep));
+    assert_eq!(renderer.to_string(), "1-{y}");
+
+    let mut output = String::new();
+    renderer.write_to(&mut output).unwrap();
+    write!(output, "|").unwrap();
+    renderer.write_to_lenient(&mut output).unwrap();
+    assert_eq!(output, "1-{y}|1-");
+}
+
+#[test]
+fn deny_unused() {
+    let deny = || RenderOptions::new().with_deny_unused(true);
+
+    let t = Template::new("{} {2:w$} {name}").unwrap();
+    let err = t
+        .render()
+        .arg(&"a")
+        .arg(&"b")
+        .arg(&"c")
+        .named("w", &3)
+        .named("name", &"n")
+        .named("extra", &"x")
+        .value(4)
+        .options(deny())
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnusedArgument { ref positional, ref named }
+            if positional == &[1, 6] && named == &["extra"]
+    ));
+    assert_eq!(err.to_string(), "unused arguments: 1, 6, `extra`");
+
+    // Width and `.*` arguments count as used, as do arguments of placeholders
+    // substituted by the missing policy.
+    let t = Template::new("{:.*} {x:w$}").unwrap();
+    let mut renderer = t.render();
+    renderer
+        .arg(&2)
+        .arg(&1.0)
+        .named("w", &4)
+        .options(deny().with_missing(MissingPolicy::Keep));
+    assert_eq!(renderer.finish().unwrap(), "1.00 {x:w$}");
+    assert_eq!(renderer.finish_lenient().unwrap(), "1.00 ");
+
+    // Slots, sources and the default options.
+    let t = Template::new("{a} {b}").unwrap();
+    let b = t.slot("b").unwrap();
+    let source = HashMap::from([("a", 1), ("unused", 2)]);
+    let mut renderer = t.render();
+    renderer.source(&source).slot(b, &2).options(deny());
+    assert_eq!(renderer.finish().unwrap(), "1 2");
+    renderer.named("c", &3);
+    assert!(matches!(
+        renderer.finish(),
+        Err(Error::UnusedArgument { ref named, .. }) if named == &["c"]
+    ));
+    renderer.options(RenderOptions::new());
+    assert_eq!(renderer.finish().unwrap(), "1 2");
+}
+
+#[test]
+fn default_values() {
+    let options = ParseOptions::new().with_defaults(true);
+    let t = Template::with_options(
+        r"{name=anonymous:*^13.4}|{0=n/a}|{user.id=\:none:?}",
+        options,
+    )
+    .unwrap();
+    assert_eq!(
+        t.render().finish().unwrap(),
+        format!("{:*^13.4}|{}|{:?}", "anonymous", "n/a", ":none")
+    );
+    let user = Value::from_iter([("id", 7)]);
+    assert_eq!(
+        t.render()
+            .value(1)
+            .named("name", &"Bob")
+            .named_value("user", user)
+            .finish()
+            .unwrap(),
+        "*****Bob*****|1|7"
+    );
+
+    // The default takes precedence over the missing policy, and the default
+    // text only supports string formatting.
+    let t = Template::with_options("{a=x} {b}", ParseOptions::new().with_defaults(true)).unwrap();
+    let keep = RenderOptions::new().with_missing(MissingPolicy::Keep);
+    assert_eq!(t.render().options(keep).finish().unwrap(), "x {b}");
+    let t = Template::with_options("{a=5:x}", ParseOptions::new().with_defaults(true)).unwrap();
+    assert!(matches!(
+        t.render().finish(),
+        Err(Error::UnsupportedTrait { .. })
+    ));
+}
+
+#[test]
+fn radix_basic() {
+    assert_fmt!("{:x}", 255);
+    assert_fmt!("{:X}", 255);
+    assert_fmt!("{:o}", 8);
+    assert_fmt!("{:b}", 5);
+}
+
+#[test]
+fn radix_negative() {
+    assert_fmt!("{:x}", -1i8);
+    assert_fmt!("{:X}", -255i32);
+    assert_fmt!("{:b}", -2i16);
+    assert_fmt!("{:o}", i64::MIN);
+    assert_fmt!("{:x}", i128::MIN);
+    assert_fmt!("{:x}", -1isize);
+}
+
+#[test]
+fn radix_unsigned() {
+    assert_fmt!("{:x}", u8::MAX);
+    assert_fmt!("{:X}", u64::MAX);
+    assert_fmt!("{:b}", u128::MAX);
+    assert_fmt!("{:o}", usize::MAX);
+}
+
+#[test]
+fn radix_alternate_width() {
+    assert_fmt!("{:#010x}", 255);
+    assert_fmt!("{:08b}", 5u8);
+    assert_fmt!("{:#o}", 8);
+    assert_fmt!("{:#X}", 255);
+    assert_fmt!("{:+#x}", 255);
+    assert_fmt!("{:10x}", 255);
+}
+
+#[test]
+fn radix_fill_align() {
+    assert_fmt!("{:*^12b}", 5);
+    assert_fmt!("{:<#8x}", 255);
+    assert_fmt!("{:>6o}", 8);
+}
+
+#[test]
+fn radix_named_and_params() {
+    assert_eq!(
+        formatx!("{reg:#0width$x}", reg = 0xbeefu16, width = 8).unwrap(),
+        format!("{reg:#0width$x}", reg = 0xbeefu16, width = 8),
+    );
+    let t = Template::new("{addr:#x} = {value:#010b}").unwrap();
+    assert_eq!(
+        t.render()
+            .named("addr", &0x40u32)
+            .named("value", &0b1011u8)
+            .finish()
+            .unwrap(),
+        "0x40 = 0b00001011"
+    );
+}
+
+#[test]
+fn radix_wrappers() {
+    use std::num::{Saturating, Wrapping};
+
+    let mut n = 255i32;
+    let r = &mut n;
+    assert_fmt!("{:x}", r);
+    let r = &&n;
+    assert_fmt!("{:#X}", r);
+    assert_fmt!("{:x}", Wrapping(200u8));
+    assert_fmt!("{:o}", Saturating(-8i16));
+    // `Box` forwards to the boxed number's traits, and the wrappers format
+    // the exponent traits, which std lacks for them, like the wrapped number.
+    let (int, float) = (Box::new(5i32), Box::new(1.5f64));
+    assert_eq!(formatx!("{:b} {:e}", int, float).unwrap(), "101 1.5e0");
+    assert_eq!(
+        formatx!("{:e} {:E}", Wrapping(1500u32), Saturating(-25i8)).unwrap(),
+        "1.5e3 -2.5E1"
+    );
+
+    // The renderer picks up the same traits through `ToArg`.
+    let t = Template::new("{:x} {:#010b} {n:e} {s:o}").unwrap();
+    let result = t
+        .render()
+        .arg(&255)
+        .arg(&5u8)
+        .named("n", &1500.0)
+        .slot(t.slot("s").unwrap(), &Wrapping(8u16))
+        .finish()
+        .unwrap();
+    assert_eq!(result, "ff 0b00000101 1.5e3 10");
+    // `Arg::new` only records `Display` and `Debug`.
+    let t = Template::new("{:x}").unwrap();
+    assert!(matches!(
+        t.render().arg_with(Arg::new(&255)).finish(),
+        Err(Error::UnsupportedTrait {
+            format_type: FormatType::LowerHex,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn exp_float() {
+    assert_fmt!("{:e}", 1234.5);
+    assert_fmt!("{:E}", 0.00012);
+    assert_fmt!("{:.3e}", 3.14159);
+    assert_fmt!("{:>12E}", 1500.0);
+    assert_fmt!("{:+e}", 2.5f32);
+    assert_fmt!("{:e}", 0.1f32);
+    assert_fmt!("{:.20e}", 0.1f32);
+    assert_fmt!("{:e}", -0.0);
+    assert_fmt!("{:e}", f64::MAX);
+    assert_fmt!("{:e}", f64::MIN_POSITIVE);
+    assert_fmt!("{:e}", f64::INFINITY);
+    assert_fmt!("{:e}", f64::NAN);
+    assert_fmt!("{:012.2e}", -42.0);
+    assert_fmt!("{:*^14.1e}", 9.99);
+}
+
+#[test]
+fn exp_integer() {
+    assert_fmt!("{:e}", 1200);
+    assert_fmt!("{:E}", -123456789i64);
+    assert_fmt!("{:.2e}", 12345u32);
+    assert_fmt!("{:e}", u128::MAX);
+    assert_fmt!("{:>10e}", 7u8);
+}
+
+#[test]
+fn unsupported_exp_format() {
+    let err = formatx!("{:e}", "1.5").unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTrait {
+            format_type: FormatType::LowerExp,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn pointer_format() {
+    let value = 42;
+    let name = String::from("buffer");
+    let t = Template::new("{name} @ {addr:p} {addr:#p} {addr:>20p} {0:p}").unwrap();
+    assert_eq!(
+        t.render()
+            .ptr(&name)
+            .named("name", &name)
+            .named_ptr("addr", &value)
+            .finish()
+            .unwrap(),
+        format!(
+            "{name} @ {addr:p} {addr:#p} {addr:>20p} {0:p}",
+            &name,
+            addr = &value,
+        ),
+    );
+}
+
+#[test]
+fn pointer_format_macro() {
+    let mut value = 42;
+    let boxed = Box::new(1.5);
+    assert_fmt!("{:p} {0:?}", &value);
+    assert_fmt!("{:#p} {0:>20p} {0}", boxed);
+    assert_eq!(
+        formatx!("{x:p} {x:x}", x = &value).unwrap(),
+        format!("{x:p} {x:x}", x = &value),
+    );
+    let r = &mut value;
+    assert_fmt!("{:p}", r);
+}
+
+#[test]
+fn unsupported_pointer_format() {
+    let value = 42;
+    let err = formatx!("{:p}", value).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTrait {
+            format_type: FormatType::Pointer,
+            ..
+        }
+    ));
+    // String slices don't record their address.
+    let err = formatx!("{:p}", "text").unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTrait {
+            format_type: FormatType::Pointer,
+            ..
+        }
+    ));
+    let err = Template::new("{}")
+        .unwrap()
+        .render()
+        .ptr(&value)
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTrait {
+            format_type: FormatType::Display,
+            ..
+        }
+    ));
+}
+
+struct DisplayOnly;
+
+impl fmt::Display for DisplayOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("display")
+    }
+}
+
+struct DebugOnly;
+
+impl fmt::Debug for DebugOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugOnly").finish()
+    }
+}
+
+#[test]
+fn arg_display_only() {
+    let t = Template::new("[{0:>9}] [{0:-<9}]").unwrap();
+    assert_eq!(
+        t.render()
+            .arg_with(Arg::display(&DisplayOnly))
+            .finish()
+            .unwrap(),
+        "[  display] [display--]"
+    );
+    let err = Template::new("{:?}")
+        .unwrap()
+        .render()
+        .arg_with(Arg::display(&DisplayOnly))
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTrait {
+            format_type: FormatType::Debug,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn arg_debug_only() {
+    let t = Template::new("{value:?} {value:#?}").unwrap();
+    assert_eq!(
+        t.render()
+            .named_with("value", Arg::debug(&DebugOnly))
+            .finish()
+            .unwrap(),
+        format!("{value:?} {value:#?}", value = DebugOnly),
+    );
+    assert!(
+        Template::new("{value}")
+            .unwrap()
+            .render()
+            .named_with("value", Arg::debug(&DebugOnly))
+            .finish()
+            .is_err()
+    );
+}
+
+#[test]
+fn arg_capabilities() {
+    let value = 255u8;
+    let arg = Arg::display(&value).with_upper_hex(&value);
+    assert!(arg.supports(FormatType::Display));
+    assert!(arg.supports(FormatType::UpperHex));
+    assert!(!arg.supports(FormatType::LowerHex));
+    assert!(!arg.supports(FormatType::Debug));
+
+    let t = Template::new("{0} {0:#06X}").unwrap();
+    assert_eq!(t.render().arg_with(arg).finish().unwrap(), "255 0x00FF");
+    assert!(
+        Template::new("{:x}")
+            .unwrap()
+            .render()
+            .arg_with(arg)
+            .finish()
+            .is_err()
+    );
+
+    assert!(!Arg::new(&1.5).supports(FormatType::LowerExp));
+    let arg = Arg::float(&1.5);
+    assert!(arg.supports(FormatType::LowerExp));
+    assert!(!arg.supports(FormatType::Binary));
+    assert!(!arg.supports(FormatType::Pointer));
+}
+
+#[test]
+fn value_matches_std() {
+    let t = Template::new("{0} {0:?} {0:>8} {0:+.2e} {0:#x} {0:08b}").unwrap();
+    assert_eq!(
+        t.render().value(-42).finish().unwrap(),
+        format!("{0} {0:?} {0:>8} {0:+.2e} {0:#x} {0:08b}", -42),
+    );
+    let t = Template::new("{0} {0:?} {0:.3} {0:E} {0:<10}|").unwrap();
+    assert_eq!(
+        t.render().value(0.1f32).finish().unwrap(),
+        format!("{0} {0:?} {0:.3} {0:E} {0:<10}|", 0.1f32),
+    );
+    let t = Template::new("{0} {0:?} {0:*^9} {0:.2}").unwrap();
+    assert_eq!(
+        t.render().value("héllo").finish().unwrap(),
+        format!("{0} {0:?} {0:*^9} {0:.2}", "héllo"),
+    );
+    let t = Template::new("{0} {0:?} {0:>6}").unwrap();
+    assert_eq!(
+        t.render().value(true).finish().unwrap(),
+        format!("{0} {0:?} {0:>6}", true),
+    );
+    assert_eq!(
+        Template::new("{:x}")
+            .unwrap()
+            .render()
+            .value(u64::MAX)
+            .finish()
+            .unwrap(),
+        format!("{:x}", u64::MAX),
+    );
+}
+
+#[test]
+fn value_keeps_type() {
+    let t = Template::new("{0} {0:?} {0:>3}|").unwrap();
+    assert_eq!(
+        t.render().value('c').finish().unwrap(),
+        format!("{0} {0:?} {0:>3}|", 'c'),
+    );
+    assert!(!Value::from('c').supports(FormatType::LowerHex));
+
+    let t = Template::new("{0:x} {0:#X} {0:o} {0:b} {0:+x} {0} {0:e}").unwrap();
+    macro_rules! assert_int {
+        ($($n:expr),*) => {$(
+            assert_eq!(
+                t.render().value($n).finish().unwrap(),
+                format!("{0:x} {0:#X} {0:o} {0:b} {0:+x} {0} {0:e}", $n),
+            );
+        )*};
+    }
+    assert_int!(
+        -1i8,
+        i8::MIN,
+        -2i16,
+        -255i32,
+        i64::MIN,
+        -1isize,
+        7i8,
+        u8::MAX
+    );
+}
+
+#[test]
+fn value_collections() {
+    let list = Value::from(vec![1, 2, 3]);
+    let map: Value = [("b", Value::from("x")), ("a", Value::from(1.5))]
+        .into_iter()
+        .collect();
+    let t = Template::new("{list} {list:?} {map} {map:?}").unwrap();
+    assert_eq!(
+        t.render()
+            .named_value("list", list.clone())
+            .named_value("map", map)
+            .finish()
+            .unwrap(),
+        r#"[1, 2, 3] [1, 2, 3] {a: 1.5, b: x} {"a": 1.5, "b": "x"}"#,
+    );
+    assert_eq!(format!("{list:#?}"), format!("{:#?}", vec![1, 2, 3]),);
+    let expected: BTreeMap<&str, Value> = [("k", Value::Null)].into_iter().collect();
+    assert_eq!(
+        format!("{:?}", Value::from(expected.clone())),
+        r#"{"k": null}"#,
+    );
+}
+
+#[test]
+fn value_owned_names() {
+    let names: Vec<String> = vec!["first".into(), "second".into()];
+    let t = Template::new("{first}-{second}-{}").unwrap();
+    let mut r = t.render();
+    r.value(Value::Null);
+    for (i, name) in names.iter().enumerate() {
+        r.named_value(name.clone(), i);
+    }
+    assert_eq!(r.finish().unwrap(), "0-1-null");
+}
+
+#[test]
+fn value_unsupported_trait() {
+    let err = Template::new("{:x}")
+        .unwrap()
+        .render()
+        .value("text")
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTrait {
+            format_type: FormatType::LowerHex,
+            ..
+        }
+    ));
+    assert!(Value::from(1.5).supports(FormatType::LowerExp));
+    assert!(!Value::from(1.5).supports(FormatType::Binary));
+    assert!(!Value::Null.supports(FormatType::Pointer));
+}
+
+#[test]
+fn source_hash_map() {
+    let env = HashMap::from([
+        ("user".to_string(), "alice".to_string()),
+        ("home".to_string(), "/home/alice".to_string()),
+    ]);
+    let t = Template::new("{user:>8}: {home}").unwrap();
+    assert_eq!(
+        t.render().source(&env).finish().unwrap(),
+        "   alice: /home/alice"
+    );
+    let t = Template::new("{user} {shell}").unwrap();
+    assert!(matches!(
+        t.render().source(&env).finish().unwrap_err(),
+        Error::MissingArgument { ref name, .. } if name == "shell"
+    ));
+    assert_eq!(t.render().source(&env).finish_lenient().unwrap(), "alice ");
+}
+
+#[test]
+fn source_btree_map_values() {
+    let map = BTreeMap::from([("count", Value::from(255)), ("width", Value::from(6))]);
+    let t = Template::new("{count:#0width$x}").unwrap();
+    assert_eq!(t.render().source(&map).finish().unwrap(), "0x00ff");
+}
+
+#[test]
+fn source_positional() {
+    let values = vec![1.5, 2.25];
+    let t = Template::new("{} {:.1} {0:e}").unwrap();
+    assert_eq!(
+        t.render().source(&values).finish().unwrap(),
+        "1.5 2.2 1.5e0"
+    );
+    let slice: &[&str] = &["a", "b"];
+    assert_eq!(
+        Template::new("{1}{0}{2}")
+            .unwrap()
+            .render()
+            .source(&slice)
+            .finish_lenient()
+            .unwrap(),
+        "ba"
+    );
+}
+
+#[test]
+fn source_precedence() {
+    let defaults = HashMap::from([("name", "default"), ("greeting", "Hello")]);
+    let overrides = BTreeMap::from([("name", "override")]);
+    let t = Template::new("{greeting}, {name}!").unwrap();
+    assert_eq!(
+        t.render()
+            .source(&overrides)
+            .source(&defaults)
+            .finish()
+            .unwrap(),
+        "Hello, override!"
+    );
+    assert_eq!(
+        t.render()
+            .named("name", &"explicit")
+            .source(&overrides)
+            .source(&defaults)
+            .finish()
+            .unwrap(),
+        "Hello, explicit!"
+    );
+}
+
+#[test]
+fn source_custom() {
+    struct Env;
+
+    impl ArgSource for Env {
+        fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+            match name {
+                "answer" => Some(Arg::integer(&42)),
+                _ => None,
+            }
+        }
+    }
+
+    let t = Template::new("{answer:#b}").unwrap();
+    assert_eq!(t.render().source(&Env).finish().unwrap(), "0b101010");
+}
+
+fn order() -> Value {
+    let customer: Value = [("email", "a@example.com")].into_iter().collect();
+    let line = |sku: &str, qty: u32| -> Value {
+        [("sku", Value::from(sku)), ("qty", Value::from(qty))]
+            .into_iter()
+            .collect()
+    };
+    [
+        ("customer", customer),
+        ("lines", Value::List(vec![line("A-1", 2), line("B-7", 10)])),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn nested_value_paths() {
+    let t = Template::new("{order.customer.email}: {order.lines[1].sku} x{order.lines[1].qty:03}")
+        .unwrap();
+    assert_eq!(
+        t.render().named_value("order", order()).finish().unwrap(),
+        "a@example.com: B-7 x010"
+    );
+    let t = Template::new("{0[1]} {lines[0]:?}").unwrap();
+    assert_eq!(
+        t.render()
+            .value(vec!["x", "y"])
+            .named_value("lines", vec![1, 2])
+            .finish()
+            .unwrap(),
+        "y 1"
+    );
+}
+
+#[test]
+fn nested_source_paths() {
+    let user = HashMap::from([("name", "Alice")]);
+    let tags = vec!["admin", "dev"];
+    let env = BTreeMap::from([("user", user)]);
+    let t = Template::new("{user.name} [{tags[1]}] {env.user.name:>6}").unwrap();
+    assert_eq!(
+        t.render()
+            .named_with("user", Arg::default().with_fields(&env["user"]))
+            .named_with("tags", Arg::default().with_fields(&tags))
+            .named_with("env", Arg::default().with_fields(&env))
+            .finish()
+            .unwrap(),
+        "Alice [dev]  Alice"
+    );
+}
+
+#[test]
+fn collection_args() {
+    let tags = vec!["admin", "dev"];
+    let user = BTreeMap::from([("name", "Alice")]);
+    let scores = HashMap::from([("a", 1)]);
+    assert_fmt!("{:?}", tags);
+    assert_fmt!("{:#?}", user);
+    assert_fmt!("{:?}", scores);
+    assert_eq!(
+        formatx!("{tags[0]} {user.name} {user:?}", tags = tags, user = user).unwrap(),
+        r#"admin Alice {"name": "Alice"}"#
+    );
+    assert!(matches!(
+        formatx!("{}", tags),
+        Err(Error::UnsupportedTrait {
+            format_type: FormatType::Display,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn nested_custom_lookup() {
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    impl ArgSource for Point {
+        fn get_named(&self, name: &str) -> Option<Arg<'_>> {
+            match name {
+                "x" => Some(Arg::new(&self.x)),
+                "y" => Some(Arg::new(&self.y)),
+                _ => None,
+            }
+        }
+    }
+
+    let p = Point { x: 3, y: -4 };
+    let t = Template::new("({p.x}, {p.y:+})").unwrap();
+    assert_eq!(
+        t.render()
+            .named_with("p", Arg::default().with_fields(&p))
+            .finish()
+            .unwrap(),
+        "(3, -4)"
+    );
+}
+
+#[test]
+fn nested_missing_path() {
+    let t = Template::new("{order.customer.phone}").unwrap();
+    let err = t
+        .render()
+        .named_value("order", order())
+        .finish()
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::MissingArgument { ref name, .. } if name == "order.customer.phone")
+    );
+    let t = Template::new("[{order.lines[5].sku}] [{0.x}]").unwrap();
+    let err = t
+        .render()
+        .value(1)
+        .named_value("order", order())
+        .finish()
+        .unwrap_err();
+    assert!(matches!(err, Error::MissingArgument { ref name, .. } if name == "order.lines[5].sku"));
+    assert_eq!(
+        t.render()
+            .value(1)
+            .named_value("order", order())
+            .finish_lenient()
+            .unwrap(),
+        "[] []"
+    );
+}
+
+#[test]
+fn write_to_fmt() {
+    let t = Template::new("{a:*^7} {b:>+6.1} {c:#06x}").unwrap();
+    let mut output = String::from("> ");
+    t.render()
+        .named("a", &"mid")
+        .named("b", &2.25)
+        .named("c", &255)
+        .write_to(&mut output)
+        .unwrap();
+    assert_eq!(
+        output,
+        format!("> {:*^7} {:>+6.1} {:#06x}", "mid", 2.25, 255)
+    );
+
+    let t = Template::new("[{a}] [{missing}]").unwrap();
+    let mut output = String::new();
+    let err = t.render().named("a", &1).write_to(&mut output).unwrap_err();
+    assert!(matches!(err, Error::MissingArgument { ref name, .. } if name == "missing"));
+    let mut output = String::new();
+    t.render()
+        .named("a", &1)
+        .write_to_lenient(&mut output)
+        .unwrap();
+    assert_eq!(output, "[1] []");
+}
+
+#[test]
+fn write_to_failing_sink() {
+    struct Full;
+    impl fmt::Write for Full {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+    let t = Template::new("{:<4}").unwrap();
+    let err = t.render().arg(&1).write_to(&mut Full).unwrap_err();
+    assert!(matches!(err, Error::Format(_)));
+}
+
+#[test]
+fn write_io() {
+    let t = Template::new("{0:-<5}|{name:?}").unwrap();
+    let mut output = Vec::new();
+    t.render()
+        .arg(&"ab")
+        .named("name", &'c')
+        .write_io(&mut output)
+        .unwrap();
+    assert_eq!(output, b"ab---|'c'");
+
+    let mut output = Vec::new();
+    let err = t.render().arg(&"ab").write_io(&mut output).unwrap_err();
+    let err = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<Error>())
+        .unwrap();
+    assert!(matches!(err, Error::MissingArgument { name, .. } if name == "name"));
+
+    let mut output = Vec::new();
+    t.render().write_io_lenient(&mut output).unwrap();
+    assert_eq!(output, b"|");
+
+    let mut sink = [0u8; 3];
+    let err = t
+        .render()
+        .arg(&"ab")
+        .named("name", &'c')
+        .write_io(&mut &mut sink[..])
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn display_renderer() {
+    let t = Template::new("{} is {age:03}").unwrap();
+    assert_eq!(
+        format!("<{}>", t.render().arg(&"Bob").named("age", &7)),
+        "<Bob is 007>"
+    );
+    assert_eq!(
+        format!("<{:*^14}>", t.render().arg(&"Bob").named("age", &7)),
+        "<**Bob is 007**>"
+    );
+    assert_eq!(
+        format!("<{:.3}>", t.render().arg(&"Bob").named("age", &7)),
+        "<Bob>"
+    );
+}
+
+#[test]
+fn display_renderer_error() {
+    use std::fmt::Write;
+
+    let t = Template::new("{} is {age}").unwrap();
+    let renderer = t.render();
+    let mut output = String::new();
+    assert!(write!(output, "{:>20}", renderer).is_err());
+    assert!(matches!(
+        renderer.take_error(),
+        Some(Error::MissingArgument { ref name, .. }) if name == "0"
+    ));
+    assert!(renderer.take_error().is_none());
+}
+
 #[test]
 fn unsupported_hex_format() {
-    let err = formatx!("{:x}", 42).unwrap_err();
+    let err = formatx!("{:x}", "hello").unwrap_err();
     assert!(matches!(
         err,
         Error::UnsupportedTrait {
@@ -180,7 +1062,8 @@ fn unsupported_hex_format() {
 
 #[test]
 fn unsupported_binary_format() {
-    assert!(formatx!("{:b}", 42).is_err());
+    assert!(formatx!("{:b}", 1.5).is_err());
+    assert!(formatx!("{:b}", true).is_err());
 }
 
 #[test]
@@ -223,6 +1106,120 @@ fn template_placeholders() {
     assert_eq!(t.placeholders(), vec!["name", "score", "name"]);
 }
 
+#[test]
+fn template_slots() {
+    let t = Template::new("{name:>width$} {0} {name:?}").unwrap();
+    let name = t.slot("name").unwrap();
+    let width = t.slot("width").unwrap();
+    assert_ne!(name, width);
+    assert!(t.slot("missing").is_none());
+
+    let result = t
+        .render()
+        .slot(name, &"Bob")
+        .slot_value(width, 5)
+        .finish()
+        .unwrap();
+    assert_eq!(result, r#"  Bob Bob "Bob""#);
+
+    // Slots take precedence over names, and unset slots fall back to names.
+    let result = t
+        .render()
+        .named("name", &"Alice")
+        .slot(name, &"Eve")
+        .named("width", &4)
+        .finish()
+        .unwrap();
+    assert_eq!(result, r#" Eve Alice "Eve""#);
+    let err = t
+        .render()
+        .slot_with(name, Arg::display(&"Eve"))
+        .named("width", &4)
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTrait {
+            format_type: FormatType::Debug,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn template_slot_from_other_template() {
+    let a = Template::new("{a} {b}").unwrap();
+    let b = Template::new("{b}").unwrap();
+    let slot = a.slot("b").unwrap();
+    let err = b.render().slot(slot, &1).finish().unwrap_err();
+    assert_eq!(err, Error::ForeignSlot);
+    assert_eq!(err.to_string(), "slot does not belong to this template");
+
+    // In range for the other template, which must not bind it to `{b}`.
+    let b = Template::new("{b} {a}").unwrap();
+    let slot = a.slot("a").unwrap();
+    let result = b.render().named("b", &1).slot(slot, &"from-a").finish();
+    assert_eq!(result, Err(Error::ForeignSlot));
+}
+
+#[test]
+fn template_slot_from_dropped_template() {
+    for _ in 0..100 {
+        let slot = Template::new("{a} {b} {c}").unwrap().slot("c").unwrap();
+        let t = Template::new("{z}").unwrap();
+        let result = t.render().slot(slot, &1).named("z", &2).finish();
+        assert_eq!(result, Err(Error::ForeignSlot));
+    }
+}
+
+#[test]
+fn template_signature() {
+    let t = Template::new("{:.*} {2:1$} {a:?} {b:a$.c$e} {a} {a.x}").unwrap();
+    let sig = t.signature();
+    let span = |start, end| Span { start, end };
+
+    assert_eq!(sig.positional.len(), 3);
+    assert_eq!(
+        sig.positional[0].uses,
+        [Usage {
+            kind: UsageKind::Precision,
+            span: span(0, 5)
+        }]
+    );
+    assert_eq!(
+        sig.positional[1].uses,
+        [
+            Usage {
+                kind: UsageKind::Value(FormatType::Display),
+                span: span(0, 5)
+            },
+            Usage {
+                kind: UsageKind::Width,
+                span: span(6, 12)
+            },
+        ]
+    );
+    assert_eq!(sig.positional[2].format_types(), [FormatType::Display]);
+    assert!(!sig.positional[2].is_count());
+
+    assert_eq!(sig.named.keys().collect::<Vec<_>>(), ["a", "b", "c"]);
+    assert_eq!(
+        sig.named["a"].format_types(),
+        [FormatType::Debug, FormatType::Display]
+    );
+    assert_eq!(sig.named["a"].uses.len(), 4);
+    assert!(sig.named["a"].is_count());
+    assert_eq!(sig.named["b"].format_types(), [FormatType::LowerExp]);
+    assert_eq!(sig.named["c"].uses[0].kind, UsageKind::Precision);
+    assert_eq!(sig.named["c"].uses[0].span, span(19, 29));
+    assert!(sig.named["c"].format_types().is_empty());
+
+    let sig = Template::new("{3} {{}}").unwrap().signature();
+    assert_eq!(sig.positional.len(), 4);
+    assert!(sig.positional[..3].iter().all(|p| p.uses.is_empty()));
+    assert!(sig.named.is_empty());
+}
+
 #[test]
 fn template_from_str() {
     let t: Template = "{:?}".parse().unwrap();
@@ -243,3 +1240,433 @@ fn zero_width() {
 fn unused_args_allowed() {
     assert_eq!(formatx!("{}", "used", "unused").unwrap(), "used");
 }
+
+#[test]
+fn template_visitor() {
+    use formatx::ast::{Argument, CountParam, FormatSpec, PathSegment, Visitor};
+
+    #[derive(Default)]
+    struct Collect(Vec<String>);
+
+    impl Visitor for Collect {
+        fn visit_literal(&mut self, text: &str, _span: Span) {
+            self.0.push(format!("lit:{text}"));
+        }
+
+        fn visit_escaped(&mut self, brace: char, span: Span) {
+            self.0.push(format!("esc:{brace}@{}", span.start));
+        }
+
+        fn visit_argument(&mut self, argument: &Argument, name: Option<&str>) {
+            match (argument, name) {
+                (Argument::Positional(i), _) => self.0.push(format!("arg:{i}")),
+                (_, Some(name)) => self.0.push(format!("arg:{name}")),
+                _ => self.0.push("arg:next".into()),
+            }
+        }
+
+        fn visit_path_segment(&mut self, segment: &PathSegment, field: Option<&str>) {
+            match (segment, field) {
+                (PathSegment::Index(i), _) => self.0.push(format!("idx:{i}")),
+                (_, field) => self.0.push(format!("field:{}", field.unwrap())),
+            }
+        }
+
+        fn visit_spec(&mut self, spec: &FormatSpec) {
+            if spec.format_type != FormatType::Display {
+                self.0.push(format!("type:{:?}", spec.format_type));
+            }
+        }
+
+        fn visit_count_param(&mut self, param: &CountParam, name: Option<&str>) {
+            match (param, name) {
+                (CountParam::Positional(i), _) => self.0.push(format!("count:{i}")),
+                (_, name) => self.0.push(format!("count:{}", name.unwrap())),
+            }
+        }
+    }
+
+    let t = Template::new("{{a}} {} {user.tags[1]:?} {0:w$.1$}").unwrap();
+    let mut collect = Collect::default();
+    t.visit(&mut collect);
+    assert_eq!(
+        collect.0,
+        [
+            "esc:{@0",
+            "lit:a",
+            "esc:}@3",
+            "lit: ",
+            "arg:next",
+            "lit: ",
+            "arg:user",
+            "field:tags",
+            "idx:1",
+            "type:Debug",
+            "lit: ",
+            "arg:0",
+            "count:w",
+            "count:1",
+        ]
+    );
+
+    let spans: Vec<&str> = t
+        .parsed()
+        .segments
+        .iter()
+        .filter_map(|segment| match segment {
+            formatx::ast::Segment::Placeholder(p) => Some(t.resolve(p.span)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(spans, ["{}", "{user.tags[1]:?}", "{0:w$.1$}"]);
+}
+
+#[test]
+fn template_builder() {
+    use formatx::ast::{Align, Sign};
+
+    let t = Template::builder()
+        .literal("{{a}} } ")
+        .literal("x")
+        .next(Spec::new())
+        .positional(1, Spec::new().with_fill('>', Align::Right).with_width(6))
+        .named(
+            "n",
+            Spec::new()
+                .with_sign(Sign::Plus)
+                .with_alternate(true)
+                .with_zero_pad(true)
+                .with_width_named("w")
+                .with_precision_arg(1)
+                .with_format_type(FormatType::LowerExp),
+        )
+        .next(Spec::new().with_star_precision())
+        .named(
+            "n",
+            Spec::new()
+                .with_width(0)
+                .with_format_type(FormatType::DebugUpperHex),
+        )
+        .build();
+    assert_eq!(
+        t.source(),
+        "{{{{a}}}} }} x{}{1:>>6}{n:+#0w$.1$e}{:.*}{n:X?}"
+    );
+
+    // The built AST matches parsing the source.
+    let parsed = Template::new(t.source()).unwrap();
+    assert_eq!(
+        format!("{:?}", t.parsed()),
+        format!("{:?}", parsed.parsed())
+    );
+
+    let render = |t: &Template| {
+        t.render()
+            .arg(&"a")
+            .arg(&3)
+            .arg(&1.23456)
+            .named("n", &1500.0)
+            .named("w", &12)
+            .finish()
+            .unwrap()
+    };
+    assert_eq!(render(&t), render(&parsed));
+    assert_eq!(
+        render(&t),
+        format!(
+            "{{{{a}}}} }} x{}{1:>>6}{n:+#0w$.1$e}{:.*}{n:X?}",
+            "a",
+            3,
+            1.23456,
+            n = 1500.0,
+            w = 12
+        )
+    );
+    assert!(t.slot("w").is_some());
+}
+
+#[test]
+#[should_panic(expected = "invalid argument name: `1x`")]
+fn template_builder_invalid_name() {
+    Template::builder().named("1x", Spec::new());
+}
+
+#[test]
+#[should_panic(expected = "invalid fill character: `{`")]
+fn template_builder_invalid_fill() {
+    Spec::new().with_fill('{', formatx::ast::Align::Left);
+}
+
+#[test]
+fn canonical_string() {
+    let cases = [
+        ("", ""),
+        ("plain {{text}}", "plain {{text}}"),
+        ("{} {} {0}", "{0} {1} {0}"),
+        ("{:} {0:}", "{0} {0}"),
+        ("{:.*} {}", "{1:.0$} {2}"),
+        ("{1:.*}", "{1:.0$}"),
+        ("{:<} {:*^} {:0} {:>-} {: <5}", "{0} {1} {2} {3} {4:<5}"),
+        ("{:+#010.3e} {:x?}", "{0:+#010.3e} {1:x?}"),
+        ("{a:w$.p$} {a:1$.0$}", "{a:w$.p$} {a:1$.0$}"),
+        ("{user.tags[0].name:?}", "{user.tags[0].name:?}"),
+        ("{:>0} {:00}", "{0} {1:00}"),
+    ];
+    for (source, expected) in cases {
+        let canonical = Template::new(source).unwrap().to_canonical_string();
+        assert_eq!(canonical, expected, "{source}");
+        // Canonical form is stable.
+        let reparsed = Template::new(canonical.as_str()).unwrap();
+        assert_eq!(reparsed.to_canonical_string(), canonical, "{source}");
+    }
+
+    let t = Template::new("{:>+8.*} [{:^w$}] {name:#x} {:.p$?}").unwrap();
+    let canonical = Template::new(t.to_canonical_string()).unwrap();
+    let render = |t: &Template| {
+        t.render()
+            .arg(&2)
+            .arg(&3.14159)
+            .arg(&"mid")
+            .arg(&2.25)
+            .named("name", &255)
+            .named("w", &7)
+            .named("p", &1)
+            .finish()
+            .unwrap()
+    };
+    assert_eq!(render(&canonical), render(&t));
+
+    let options = ParseOptions::new().with_defaults(true);
+    let t = Template::with_options(r"{name=a\:b\{\}\\c:>9}", options.clone()).unwrap();
+    let canonical = t.to_canonical_string();
+    assert_eq!(canonical, r"{name=a\:b\{\}\\c:>9}");
+    let reparsed = Template::with_options(canonical, options).unwrap();
+    assert_eq!(
+        reparsed.render().finish().unwrap(),
+        t.render().finish().unwrap()
+    );
+}
+
+#[test]
+fn diagnostics() {
+    let source = "Hello {name:>5";
+    let err = Template::new(source).unwrap_err();
+    assert_eq!(
+        err.diagnostic(source).to_string(),
+        "error: unmatched `{`\n --> 1:7\n  |\n1 | Hello {name:>5\n  |       ^^^^^^^^\n  = help: use `{{` for a literal `{`\n"
+    );
+
+    let source = format!("{}\tné {{name:>5}} ok", "\n".repeat(9));
+    let t = Template::new(source.as_str()).unwrap();
+    let err = t.render().finish().unwrap_err();
+    assert_eq!(err.span(), Some(Span { start: 14, end: 23 }));
+    let diagnostic = err.diagnostic(&source);
+    assert_eq!(diagnostic.location(), Some((10, 5)));
+    assert_eq!(
+        diagnostic.to_string(),
+        "error: missing argument: `name`\n  --> 10:5\n   |\n10 | \tné {name:>5} ok\n   | \t   ^^^^^^^^^\n   = help: provide an argument for `name`\n"
+    );
+
+    let source = "{:x}";
+    let err = Template::new(source)
+        .unwrap()
+        .render()
+        .arg(&"s")
+        .finish()
+        .unwrap_err();
+    assert_eq!(
+        err.diagnostic(source).with_help("use `{}`").to_string(),
+        "error: unsupported format trait: `LowerHex`\n --> 1:1\n  |\n1 | {:x}\n  | ^^^^\n  = help: use `{}`\n"
+    );
+
+    let err = Error::ForeignSlot;
+    assert_eq!(err.span(), None);
+    assert_eq!(
+        err.diagnostic("{}").without_help().to_string(),
+        "error: slot does not belong to this template\n"
+    );
+}
+
+#[test]
+fn count_argument_errors() {
+    let t = Template::new("ab {:>3$} {x:.w$} {:.*}").unwrap();
+    let placeholder = Span { start: 3, end: 9 };
+
+    let err = t.render().arg(&1).finish().unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::CountArgument { name, value: None, span, placeholder: p }
+            if name == "3" && *span == Span { start: 6, end: 8 } && *p == placeholder
+    ));
+    assert_eq!(err.to_string(), "missing count argument: `3`");
+
+    let err = t
+        .render()
+        .arg(&1)
+        .arg(&2)
+        .arg(&3)
+        .arg(&"wide")
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::CountArgument { name, value: Some(value), span, .. }
+            if name == "3" && value == "wide" && *span == Span { start: 6, end: 8 }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "count argument `3` is not a valid usize: `wide`"
+    );
+
+    let err = t
+        .render()
+        .arg(&1)
+        .arg(&2)
+        .arg(&3)
+        .arg(&4)
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::CountArgument { name, value: None, span, placeholder }
+            if name == "w"
+                && *span == Span { start: 13, end: 16 }
+                && *placeholder == Span { start: 10, end: 17 }
+    ));
+
+    // `.*` takes implicit index 1, after `{:>3$}` took index 0.
+    let err = t
+        .render()
+        .arg(&1)
+        .arg(&"s")
+        .arg(&3)
+        .arg(&4)
+        .named("x", &1.5)
+        .named("w", &2)
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::CountArgument { name, value: Some(value), span, placeholder }
+            if name == "1"
+                && value == "s"
+                && *span == Span { start: 20, end: 22 }
+                && *placeholder == Span { start: 18, end: 23 }
+    ));
+    assert_eq!(
+        err.diagnostic(t.source()).to_string(),
+        "error: count argument `1` is not a valid usize: `s`\n --> 1:21\n  |\n1 | ab {:>3$} {x:.w$} {:.*}\n  |                     ^^\n  = help: provide an integer argument for `1` to use as width or precision\n"
+    );
+}
+
+#[test]
+fn parse_all_errors() {
+    use formatx::ParseErrorKind;
+
+    let source = "a {x-y} b {0:>5 {ok:?} } c {é} {";
+    let (t, errors) = Template::parse_all_errors(source, ParseOptions::new());
+    assert_eq!(
+        errors,
+        [
+            Error::Parse {
+                span: Span { start: 4, end: 5 },
+                kind: ParseErrorKind::InvalidArgument('-'),
+            },
+            Error::Parse {
+                span: Span { start: 15, end: 16 },
+                kind: ParseErrorKind::UnknownFormatType(' '),
+            },
+            Error::Parse {
+                span: Span { start: 23, end: 24 },
+                kind: ParseErrorKind::UnmatchedClose,
+            },
+            Error::Parse {
+                span: Span { start: 28, end: 30 },
+                kind: ParseErrorKind::InvalidArgument('é'),
+            },
+            Error::Parse {
+                span: Span { start: 33, end: 33 },
+                kind: ParseErrorKind::UnexpectedEnd,
+            },
+        ]
+    );
+    assert_eq!(t.placeholders(), ["ok"]);
+    assert_eq!(Template::new(source).unwrap_err(), errors[0]);
+
+    let (t, errors) = Template::parse_all_errors("{a} {{b}}", ParseOptions::new());
+    assert!(errors.is_empty());
+    assert_eq!(t.render().named("a", &1).finish().unwrap(), "1 {b}");
+
+    let options = ParseOptions::new().with_defaults(true);
+    let (t, errors) = Template::parse_all_errors("{a=\\x} {b=ok}", options);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(t.render().finish_lenient().unwrap(), " ok");
+}
+
+#[test]
+fn missing_argument_suggestions() {
+    let t = Template::new("{user} {usre} {agee}").unwrap();
+    let err = t
+        .render()
+        .named("user", &"a")
+        .named("age", &1)
+        .finish()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::MissingArgument {
+            name: "usre".to_string(),
+            span: Span { start: 7, end: 13 },
+            suggestions: vec!["user".to_string()],
+        }
+    );
+    assert!(
+        err.diagnostic(t.source())
+            .to_string()
+            .contains("= help: did you mean `user`?")
+    );
+
+    // Names used elsewhere in the template are candidates too.
+    let err = t
+        .render()
+        .named("user", &"a")
+        .named("usre", &"b")
+        .finish()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::MissingArgument { ref name, ref suggestions, .. }
+            if name == "agee" && suggestions.is_empty()
+    ));
+    let t = Template::new("{name} {nmae}").unwrap();
+    let err = t.render().named("nme", &1).finish().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::MissingArgument { ref name, ref suggestions, .. }
+            if name == "name" && *suggestions == ["nme", "nmae"]
+    ));
+    assert!(
+        err.diagnostic(t.source())
+            .to_string()
+            .contains("= help: did you mean one of `nme`, `nmae`?")
+    );
+}
+
+#[test]
+fn named_args_with_positional_source() {
+    let values = vec!["a", "b"];
+    let t = Template::new("{0} {1} {x} {}").unwrap();
+    let result = t.render().source(&values).named("x", &1).finish().unwrap();
+    assert_eq!(result, "1 b 1 1");
+
+    // As in `format!`, named arguments take up positional indices too.
+    assert_eq!(formatx!("{} {0}", x = 1).unwrap(), "1 1");
+    let result = t
+        .render()
+        .arg(&"z")
+        .named("x", &1)
+        .source(&values)
+        .finish()
+        .unwrap();
+    assert_eq!(result, "z 1 1 z");
+}
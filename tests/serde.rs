@@ -0,0 +1,113 @@
+#![cfg(feature = "serde")]
+
+use formatx::{Error, Template, Value, to_value};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+struct Line {
+    sku: &'static str,
+    qty: u32,
+}
+
+#[derive(Serialize)]
+enum Status {
+    Open,
+    Held(String),
+    Shipped { carrier: &'static str },
+}
+
+#[derive(Serialize)]
+struct Order {
+    id: u64,
+    price: f32,
+    paid: bool,
+    note: Option<String>,
+    lines: Vec<Line>,
+    status: Status,
+}
+
+fn order(status: Status) -> Order {
+    Order {
+        id: 255,
+        price: 0.1,
+        paid: true,
+        note: None,
+        lines: vec![
+            Line { sku: "A-1", qty: 2 },
+            Line {
+                sku: "B-2",
+                qty: 10,
+            },
+        ],
+        status,
+    }
+}
+
+fn render(template: &str, value: &Value) -> Result<String, Error> {
+    Template::new(template)?.render().source(value).finish()
+}
+
+#[test]
+fn scalars_match_std() {
+    let value = to_value(&order(Status::Open)).unwrap();
+    assert_eq!(
+        render("{id:#x} {price} {price:?} {paid} {paid:?}", &value).unwrap(),
+        format!("{:#x} {} {:?} {} {:?}", 255, 0.1f32, 0.1f32, true, true),
+    );
+    assert_eq!(render("{note}", &value).unwrap(), "null");
+    assert_eq!(
+        render("{0:?} {1:x}", &to_value(&('c', -1i8)).unwrap()).unwrap(),
+        "'c' ff"
+    );
+    assert_eq!(render("{status:?}", &value).unwrap(), r#""Open""#);
+}
+
+#[test]
+fn nested_paths() {
+    let value = to_value(&order(Status::Open)).unwrap();
+    assert_eq!(
+        render("{lines[1].sku:>5}x{lines[1].qty:03}", &value).unwrap(),
+        "  B-2x010",
+    );
+    assert!(matches!(
+        render("{lines[2].sku}", &value),
+        Err(Error::MissingArgument { name, .. }) if name == "lines[2].sku"
+    ));
+}
+
+#[test]
+fn enum_variants() {
+    let held = to_value(&order(Status::Held("fraud".into()))).unwrap();
+    assert_eq!(render("{status.Held}", &held).unwrap(), "fraud");
+
+    let shipped = to_value(&order(Status::Shipped { carrier: "UPS" })).unwrap();
+    assert_eq!(render("{status.Shipped.carrier}", &shipped).unwrap(), "UPS");
+}
+
+#[test]
+fn map_keys() {
+    let map = BTreeMap::from([(1, "one"), (2, "two")]);
+    assert_eq!(
+        to_value(&map).unwrap(),
+        Value::from_iter([("1", "one"), ("2", "two")]),
+    );
+
+    let map = BTreeMap::from([((1, 2), "pair")]);
+    assert!(matches!(to_value(&map), Err(Error::Serialize(_))));
+}
+
+#[test]
+fn out_of_range() {
+    assert_eq!(to_value(&5i128).unwrap(), Value::from(5i64));
+    let err = to_value(&u128::MAX).unwrap_err();
+    assert!(matches!(err, Error::Serialize(_)));
+    assert_eq!(err.span(), None);
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "serialization error: integer `{}` is out of range",
+            u128::MAX
+        )
+    );
+}